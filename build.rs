@@ -1,7 +1,27 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+const SCHEMA_DIR: &str = "assets/schemas";
+
 fn main() {
+    // Compile the GSettings schema so it can ship inside the gresource bundle
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let compiled_dir = out_dir.join("schemas");
+    std::fs::create_dir_all(&compiled_dir).expect("Failed to create the schema output directory");
+
+    let status = Command::new("glib-compile-schemas")
+        .arg("--strict")
+        .arg("--targetdir")
+        .arg(&compiled_dir)
+        .arg(SCHEMA_DIR)
+        .status()
+        .expect("Failed to run glib-compile-schemas");
+    assert!(status.success(), "glib-compile-schemas failed for {}", SCHEMA_DIR);
+    println!("cargo:rerun-if-changed={}", SCHEMA_DIR);
+
     glib_build_tools::compile_resources(
-        &["assets"],
+        &[PathBuf::from("assets"), out_dir],
         "assets/resources.xml",
         "compiled.gresource",
     );
-}
\ No newline at end of file
+}
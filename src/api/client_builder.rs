@@ -0,0 +1,282 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+use crate::api::jikan::{JikanClient, JikanError};
+use crate::api::response_cache::ResponseCache;
+use crate::api::transport::{JikanTransport, ReqwestTransport};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.jikan.moe/v4";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const SETTINGS_FILE: &str = "jikan.json";
+
+// Settings installed by the app, applied over the settings file
+static INSTALLED: RwLock<Option<JikanSettings>> = RwLock::new(None);
+// The settings file, read once rather than for every client
+static FILE_SETTINGS: LazyLock<JikanSettings> = LazyLock::new(JikanSettings::load_file);
+
+// Environment variables that take precedence over the settings file
+const ENV_BASE_URL: &str = "WAIFU_VIEWER_JIKAN_URL";
+const ENV_TIMEOUT: &str = "WAIFU_VIEWER_TIMEOUT_SECS";
+const ENV_CONNECT_TIMEOUT: &str = "WAIFU_VIEWER_CONNECT_TIMEOUT_SECS";
+const ENV_USER_AGENT: &str = "WAIFU_VIEWER_USER_AGENT";
+const ENV_PROXY: &str = "WAIFU_VIEWER_PROXY";
+
+// User overrides for how the client reaches Jikan; unset fields keep the defaults
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JikanSettings {
+    pub base_url: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub user_agent: Option<String>,
+    // Proxy URL for every request, e.g. `http://proxy.example:3128`
+    pub proxy: Option<String>,
+    // Results per page for listings and series searches
+    pub page_size: Option<u32>,
+    // Leave adult anime and manga out of listings and series searches
+    pub sfw: Option<bool>,
+    pub cache_max_bytes: Option<u64>,
+}
+
+impl JikanSettings {
+    // `jikan.json` from the config directory, with any installed settings on top.
+    // The file is a developer override, mainly for the CLI; the app's preferences are
+    // installed over it. The file is read on first use, so edits need a restart; a missing
+    // or broken file means no overrides.
+    pub fn load() -> Self {
+        let file = FILE_SETTINGS.clone();
+        match INSTALLED.read().ok().and_then(|installed| installed.clone()) {
+            Some(installed) => installed.or(file),
+            None => file,
+        }
+    }
+
+    fn load_file() -> Self {
+        let Some(mut path) = dirs::config_dir() else {
+            return Self::default();
+        };
+        path.push("waifu-viewer");
+        path.push(SETTINGS_FILE);
+
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self {
+            base_url: env_string(ENV_BASE_URL),
+            timeout_secs: env_secs(ENV_TIMEOUT),
+            connect_timeout_secs: env_secs(ENV_CONNECT_TIMEOUT),
+            user_agent: env_string(ENV_USER_AGENT),
+            proxy: env_string(ENV_PROXY),
+            ..Self::default()
+        }
+    }
+
+    // Makes every client created afterwards use these settings over the file, e.g. the app's preferences
+    pub fn install(settings: Self) {
+        if let Ok(mut installed) = INSTALLED.write() {
+            *installed = Some(settings);
+        }
+    }
+
+    // These settings, with unset fields taken from `fallback`
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            base_url: self.base_url.or(fallback.base_url),
+            timeout_secs: self.timeout_secs.or(fallback.timeout_secs),
+            connect_timeout_secs: self.connect_timeout_secs.or(fallback.connect_timeout_secs),
+            user_agent: self.user_agent.or(fallback.user_agent),
+            proxy: self.proxy.or(fallback.proxy),
+            page_size: self.page_size.or(fallback.page_size),
+            sfw: self.sfw.or(fallback.sfw),
+            cache_max_bytes: self.cache_max_bytes.or(fallback.cache_max_bytes),
+        }
+    }
+}
+
+fn env_string(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn env_secs(name: &str) -> Option<u64> {
+    let value = env_string(name)?;
+    match value.parse() {
+        Ok(seconds) => Some(seconds),
+        Err(_) => {
+            warn!("Ignoring {}={}: expected a number of seconds", name, value);
+            None
+        }
+    }
+}
+
+// Configures a JikanClient. Timeouts, user agent and proxy only apply to the default
+// reqwest transport; a custom transport is used exactly as given.
+pub struct JikanClientBuilder {
+    base_url: String,
+    timeout: Duration,
+    connect_timeout: Duration,
+    user_agent: String,
+    proxy: Option<String>,
+    page_size: Option<u32>,
+    sfw: bool,
+    transport: Option<Arc<dyn JikanTransport>>,
+    // `None` keeps the default on-disk cache
+    cache: Option<Option<ResponseCache>>,
+    cache_max_bytes: Option<u64>,
+}
+
+impl JikanClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            user_agent: format!("waifu-viewer/{}", env!("CARGO_PKG_VERSION")),
+            proxy: None,
+            page_size: None,
+            sfw: false,
+            transport: None,
+            cache: None,
+            cache_max_bytes: None,
+        }
+    }
+
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    // Limit for a whole request, from connecting until the body has been read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    // Results per page for top listings and series searches; Jikan's own default when unset
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    pub fn sfw(mut self, sfw: bool) -> Self {
+        self.sfw = sfw;
+        self
+    }
+
+    pub fn transport(mut self, transport: impl JikanTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    // Replaces the response cache, or disables caching with `None`
+    pub fn cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    // Size limit for the default on-disk cache
+    pub fn cache_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.cache_max_bytes = Some(max_bytes);
+        self
+    }
+
+    // Applies every field the settings set, leaving the rest as they are
+    pub fn settings(mut self, settings: &JikanSettings) -> Self {
+        if let Some(base_url) = &settings.base_url {
+            self = self.base_url(base_url);
+        }
+        if let Some(seconds) = settings.timeout_secs {
+            self = self.timeout(Duration::from_secs(seconds));
+        }
+        if let Some(seconds) = settings.connect_timeout_secs {
+            self = self.connect_timeout(Duration::from_secs(seconds));
+        }
+        if let Some(user_agent) = &settings.user_agent {
+            self = self.user_agent(user_agent);
+        }
+        if let Some(proxy) = &settings.proxy {
+            self = self.proxy(proxy);
+        }
+        if let Some(page_size) = settings.page_size {
+            self = self.page_size(page_size);
+        }
+        if let Some(sfw) = settings.sfw {
+            self = self.sfw(sfw);
+        }
+        if let Some(max_bytes) = settings.cache_max_bytes {
+            self = self.cache_max_bytes(max_bytes);
+        }
+        self
+    }
+
+    // The `WAIFU_VIEWER_*` environment variables, for pointing a run at a mirror or mock server
+    pub fn env(self) -> Self {
+        self.settings(&JikanSettings::from_env())
+    }
+
+    pub fn build(self) -> Result<JikanClient, JikanError> {
+        reqwest::Url::parse(&self.base_url)
+            .map_err(|e| JikanError::InvalidRequest(format!("base URL {}: {}", self.base_url, e)))?;
+
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut client = reqwest::Client::builder()
+                    .timeout(self.timeout)
+                    .connect_timeout(self.connect_timeout)
+                    .user_agent(self.user_agent);
+                if let Some(proxy) = &self.proxy {
+                    let proxy = reqwest::Proxy::all(proxy)
+                        .map_err(|e| JikanError::InvalidRequest(format!("proxy {}: {}", proxy, e)))?;
+                    client = client.proxy(proxy);
+                }
+                Arc::new(ReqwestTransport::new(client.build()?))
+            }
+        };
+
+        let cache = match self.cache {
+            Some(cache) => cache,
+            None => ResponseCache::new().map(|mut cache| {
+                if let Some(max_bytes) = self.cache_max_bytes {
+                    cache.set_max_bytes(max_bytes);
+                }
+                cache
+            }),
+        };
+        Ok(JikanClient::from_parts(transport, self.base_url, cache).with_listing(self.page_size, self.sfw))
+    }
+}
+
+impl Default for JikanClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
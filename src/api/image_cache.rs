@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::PathBuf;
+use tokio::task;
+
+use crate::api::response_cache::{fnv1a_hash, touch_file, write_tracked};
+
+const CACHE_DIR: &str = "waifu-viewer";
+const IMAGES_DIR: &str = "images";
+const DEFAULT_MAX_BYTES: u64 = 200 * 1024 * 1024;
+
+// On-disk cache of original image bytes keyed by URL, evicted least-recently-used first
+#[derive(Debug, Clone)]
+pub struct ImageCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl ImageCache {
+    // Opens the cache under the user's cache directory, if there is one
+    pub fn new() -> Option<Self> {
+        let mut dir = dirs::cache_dir()?;
+        dir.push(CACHE_DIR);
+        dir.push(IMAGES_DIR);
+        Some(Self::with_dir(dir, DEFAULT_MAX_BYTES))
+    }
+
+    pub fn with_dir(dir: PathBuf, max_bytes: u64) -> Self {
+        Self { dir, max_bytes }
+    }
+
+    pub fn set_max_bytes(&mut self, max_bytes: u64) {
+        self.max_bytes = max_bytes;
+    }
+
+    pub fn load(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(url);
+        let bytes = fs::read(&path).ok()?;
+        touch_file(&path);
+        Some(bytes)
+    }
+
+    // Writes on a blocking thread; like `ResponseCache`, the directory is only scanned again
+    // once its tracked size passes `max_bytes`
+    pub async fn store(&self, url: &str, bytes: Vec<u8>) -> Result<(), String> {
+        let dir = self.dir.clone();
+        let path = self.entry_path(url);
+        let max_bytes = self.max_bytes;
+
+        task::spawn_blocking(move || write_tracked(&dir, &path, &bytes, max_bytes, "img"))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.img", fnv1a_hash(url)))
+    }
+}
\ No newline at end of file
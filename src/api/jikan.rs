@@ -1,8 +1,32 @@
+use log::{debug, info, trace, warn};
 use reqwest;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::models::character::Character;
+use crate::api::client_builder::{JikanClientBuilder, JikanSettings};
+use crate::api::rate_limiter::RateLimiter;
+use crate::api::response_cache::{CacheEntry, ResponseCache};
+use crate::api::search_query::CharacterSearchQuery;
+use crate::api::transport::{JikanTransport, TransportRequest, TransportResponse};
+use crate::models::anime::Anime;
+use crate::models::character::{CastMember, Character, CharacterFull, CharacterPicture, CharacterVoice};
+use crate::models::manga::Manga;
+use crate::models::person::PersonFull;
+
+// Retries after the first attempt for 429 and 5xx responses
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+// Upper bound for any single wait, including server-provided Retry-After values
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+// Random rolls allowed per requested character before a batch gives up on duplicates
+const RANDOM_ATTEMPTS_PER_CHARACTER: usize = 3;
+// How much of each response body trace logging shows
+const BODY_PREVIEW_BYTES: usize = 1000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JikanPagination {
@@ -10,10 +34,17 @@ pub struct JikanPagination {
     pub has_next_page: bool,
 }
 
+// A page of results from a listing endpoint; characters unless stated otherwise
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JikanResponse {
+pub struct JikanResponse<T = Character> {
     pub pagination: Option<JikanPagination>,
-    pub data: Vec<Character>,
+    pub data: Vec<T>,
+}
+
+// Envelope for endpoints that return a single object under `data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JikanData<T> {
+    pub data: T,
 }
 
 
@@ -22,14 +53,24 @@ pub struct JikanResponse {
 #[derive(Debug)]
 pub enum JikanError {
     Network(reqwest::Error),
+    // A custom transport could not get a response
+    Transport(String),
+    // A URL, proxy or query the client could not build a request from
+    InvalidRequest(String),
     JsonParsing(serde_json::Error),
+    RateLimited,
+    Http { status: StatusCode, body: String },
 }
 
 impl std::fmt::Display for JikanError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             JikanError::Network(e) => write!(f, "Network error: {}", e),
+            JikanError::Transport(message) => write!(f, "Network error: {}", message),
+            JikanError::InvalidRequest(message) => write!(f, "Invalid request: {}", message),
             JikanError::JsonParsing(e) => write!(f, "JSON parsing error: {}", e),
+            JikanError::RateLimited => write!(f, "Rate limited by the Jikan API, please try again shortly"),
+            JikanError::Http { status, .. } => write!(f, "HTTP error: {}", status),
         }
     }
 }
@@ -48,62 +89,364 @@ impl From<serde_json::Error> for JikanError {
     }
 }
 
+enum FetchOutcome {
+    Modified { body: String, etag: Option<String> },
+    NotModified,
+}
+
 pub struct JikanClient {
-    client: reqwest::Client,
+    transport: Arc<dyn JikanTransport>,
     base_url: String,
+    cache: Option<ResponseCache>,
+    page_size: Option<u32>,
+    sfw: bool,
 }
 
 impl JikanClient {
+    // The default client, with overrides from jikan.json and then the environment
     pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            base_url: "https://api.jikan.moe/v4".to_string(),
+        Self::builder()
+            .settings(&JikanSettings::load())
+            .env()
+            .build()
+            .unwrap_or_else(|e| {
+                warn!("Ignoring Jikan client overrides: {}", e);
+                Self::builder().build().expect("default Jikan client configuration is valid")
+            })
+    }
+
+    pub fn builder() -> JikanClientBuilder {
+        JikanClientBuilder::new()
+    }
+
+    pub(crate) fn from_parts(transport: Arc<dyn JikanTransport>, base_url: String, cache: Option<ResponseCache>) -> Self {
+        Self { transport, base_url, cache, page_size: None, sfw: false }
+    }
+
+    pub(crate) fn with_listing(mut self, page_size: Option<u32>, sfw: bool) -> Self {
+        self.page_size = page_size;
+        self.sfw = sfw;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // Replaces the response cache, or disables caching with `None`
+    pub fn with_cache(mut self, cache: Option<ResponseCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub async fn get_top_characters(&self, page: u32) -> Result<JikanResponse, JikanError> {
+        let url = self.listing_url("top/characters", page, false)?;
+        self.get_json(&url).await
+    }
+    
+    pub async fn search_characters(&self, query: &CharacterSearchQuery) -> Result<JikanResponse, JikanError> {
+        let url = self.endpoint_url("characters", query)?;
+        self.get_json(&url).await
+    }
+
+    pub async fn get_character(&self, mal_id: u32) -> Result<Character, JikanError> {
+        let url = format!("{}/characters/{}", self.base_url, mal_id);
+        let response: JikanData<Character> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_character_full(&self, mal_id: u32) -> Result<CharacterFull, JikanError> {
+        let url = format!("{}/characters/{}/full", self.base_url, mal_id);
+        let response: JikanData<CharacterFull> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_character_pictures(&self, mal_id: u32) -> Result<Vec<CharacterPicture>, JikanError> {
+        let url = format!("{}/characters/{}/pictures", self.base_url, mal_id);
+        let response: JikanData<Vec<CharacterPicture>> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_character_voices(&self, mal_id: u32) -> Result<Vec<CharacterVoice>, JikanError> {
+        let url = format!("{}/characters/{}/voices", self.base_url, mal_id);
+        let response: JikanData<Vec<CharacterVoice>> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_person_full(&self, mal_id: u32) -> Result<PersonFull, JikanError> {
+        let url = format!("{}/people/{}/full", self.base_url, mal_id);
+        let response: JikanData<PersonFull> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    // Every call returns a different character, so this endpoint is never cached
+    pub async fn get_random_character(&self) -> Result<Character, JikanError> {
+        let url = format!("{}/random/characters", self.base_url);
+        let response: JikanData<Character> = self.get_uncached_json(&url).await?;
+        Ok(response.data)
+    }
+
+    // Rolls up to `count` distinct random characters, skipping ids in `exclude`. Duplicates
+    // are re-rolled a bounded number of times, so fewer may come back from a small pool.
+    // Once something has been rolled, a failure ends the batch early instead of discarding it.
+    pub async fn get_random_characters(&self, count: usize, exclude: &HashSet<u32>) -> Result<Vec<Character>, JikanError> {
+        let mut characters: Vec<Character> = Vec::with_capacity(count);
+        let mut seen = exclude.clone();
+        let max_attempts = count * RANDOM_ATTEMPTS_PER_CHARACTER;
+
+        for _ in 0..max_attempts {
+            if characters.len() >= count {
+                break;
+            }
+            match self.get_random_character().await {
+                Ok(character) => {
+                    if seen.insert(character.mal_id) {
+                        characters.push(character);
+                    }
+                }
+                Err(e) if characters.is_empty() => return Err(e),
+                Err(e) => {
+                    info!("Stopping random batch early: {}", e);
+                    break;
+                }
+            }
+        }
+
+        Ok(characters)
+    }
+
+    pub async fn get_top_anime(&self, page: u32) -> Result<JikanResponse<Anime>, JikanError> {
+        let url = self.listing_url("top/anime", page, true)?;
+        self.get_json(&url).await
+    }
+
+    pub async fn search_anime(&self, text: &str, page: u32) -> Result<JikanResponse<Anime>, JikanError> {
+        let url = self.search_url("anime", text, page)?;
+        self.get_json(&url).await
+    }
+
+    pub async fn get_anime(&self, mal_id: u32) -> Result<Anime, JikanError> {
+        let url = format!("{}/anime/{}", self.base_url, mal_id);
+        let response: JikanData<Anime> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    // The full cast in one response; this endpoint is not paginated
+    pub async fn get_anime_characters(&self, mal_id: u32) -> Result<Vec<CastMember>, JikanError> {
+        let url = format!("{}/anime/{}/characters", self.base_url, mal_id);
+        let response: JikanData<Vec<CastMember>> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_top_manga(&self, page: u32) -> Result<JikanResponse<Manga>, JikanError> {
+        let url = self.listing_url("top/manga", page, true)?;
+        self.get_json(&url).await
+    }
+
+    pub async fn search_manga(&self, text: &str, page: u32) -> Result<JikanResponse<Manga>, JikanError> {
+        let url = self.search_url("manga", text, page)?;
+        self.get_json(&url).await
+    }
+
+    pub async fn get_manga(&self, mal_id: u32) -> Result<Manga, JikanError> {
+        let url = format!("{}/manga/{}", self.base_url, mal_id);
+        let response: JikanData<Manga> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    pub async fn get_manga_characters(&self, mal_id: u32) -> Result<Vec<CastMember>, JikanError> {
+        let url = format!("{}/manga/{}/characters", self.base_url, mal_id);
+        let response: JikanData<Vec<CastMember>> = self.get_json(&url).await?;
+        Ok(response.data)
+    }
+
+    // `/{endpoint}?q=...&page=...`
+    fn search_url(&self, endpoint: &str, text: &str, page: u32) -> Result<String, JikanError> {
+        let mut query = vec![("q", text.trim().to_string()), ("page", page.to_string())];
+        query.extend(self.listing_params(true));
+        self.endpoint_url(endpoint, &query)
+    }
+
+    // `/{endpoint}?page=...`, plus the page size and SFW filter when configured
+    fn listing_url(&self, endpoint: &str, page: u32, filterable: bool) -> Result<String, JikanError> {
+        let mut query = vec![("page", page.to_string())];
+        query.extend(self.listing_params(filterable));
+        self.endpoint_url(endpoint, &query)
+    }
+
+    // Anime and manga endpoints are `filterable`; character listings have no adult filter
+    fn listing_params(&self, filterable: bool) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(page_size) = self.page_size {
+            params.push(("limit", page_size.to_string()));
         }
+        if filterable && self.sfw {
+            params.push(("sfw", "true".to_string()));
+        }
+        params
     }
 
-    pub async fn get_top_characters(&self) -> Result<Vec<Character>, JikanError> {
-        let url = format!("{}/top/characters", self.base_url);
-        println!("Making request to: {}", url);
-        
-        let response = self.client.get(&url).send().await?;
-        println!("Response status: {}", response.status());
-        
-        // Log response body for debugging
-        let text = response.text().await?;
-        println!("Response body length: {}", text.len());
-        // Only log first 1000 characters to avoid overwhelming output
-        if text.len() > 1000 {
-            println!("First 1000 chars of response body: {}", &text[..1000]);
-        } else {
-            println!("Response body: {}", text);
+    // Encodes the parameters properly so names with `&`, `#` or non-ASCII text survive
+    fn endpoint_url<Q: Serialize + ?Sized>(&self, endpoint: &str, query: &Q) -> Result<String, JikanError> {
+        let mut url = reqwest::Url::parse(&format!("{}/{}", self.base_url, endpoint))
+            .map_err(|e| JikanError::InvalidRequest(e.to_string()))?;
+        let encoded = serde_urlencoded::to_string(query)
+            .map_err(|e| JikanError::InvalidRequest(e.to_string()))?;
+        if !encoded.is_empty() {
+            url.set_query(Some(&encoded));
         }
-        
-        // Try to parse JSON from text
-        let jikan_response: JikanResponse = serde_json::from_str(&text)?;
-        Ok(jikan_response.data)
+        Ok(url.to_string())
     }
-    
-    pub async fn search_characters(&self, query: &str) -> Result<Vec<Character>, JikanError> {
-        let url = format!("{}/characters?q={}", self.base_url, query);
-        println!("Making request to: {}", url);
-        
-        let response = self.client.get(&url).send().await?;
-        println!("Response status: {}", response.status());
-        
-        // Log response body for debugging
-        let text = response.text().await?;
-        println!("Response body length: {}", text.len());
-        // Only log first 1000 characters to avoid overwhelming output
-        if text.len() > 1000 {
-            println!("First 1000 chars of response body: {}", &text[..1000]);
-        } else {
-            println!("Response body: {}", text);
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, JikanError> {
+        let Some(cache) = &self.cache else {
+            return self.get_uncached_json(url).await;
+        };
+
+        // Cache keys are the endpoint and query, independent of the base URL
+        let key = url.strip_prefix(&self.base_url).unwrap_or(url);
+        let cached = cache.load(key).await;
+
+        if let Some(entry) = &cached
+            && cache.is_fresh(entry)
+            && let Ok(value) = serde_json::from_str(&entry.body)
+        {
+            debug!(key; "Serving cached response");
+            cache.touch(key).await;
+            return Ok(value);
+        }
+
+        let etag = cached.as_ref().and_then(|entry| entry.etag.as_deref());
+        match self.fetch_text(url, etag).await {
+            Ok(FetchOutcome::Modified { body, etag }) => {
+                let value = serde_json::from_str(&body)?;
+                if let Err(e) = cache.store(&CacheEntry::new(key, etag, body)).await {
+                    warn!(key; "Failed to cache response: {}", e);
+                }
+                Ok(value)
+            }
+            Ok(FetchOutcome::NotModified) => {
+                // Only sent If-None-Match when there was an entry to revalidate
+                let mut entry = cached.ok_or(JikanError::Http {
+                    status: StatusCode::NOT_MODIFIED,
+                    body: String::new(),
+                })?;
+                entry.mark_revalidated();
+                let value = serde_json::from_str(&entry.body)?;
+                if let Err(e) = cache.store(&entry).await {
+                    warn!(key; "Failed to cache response: {}", e);
+                }
+                Ok(value)
+            }
+            Err(e) if Self::can_serve_stale(&e) => {
+                // Offline or Jikan is struggling: fall back to whatever we last saw
+                let Some(entry) = cached else {
+                    return Err(e);
+                };
+                let Ok(value) = serde_json::from_str(&entry.body) else {
+                    return Err(e);
+                };
+                warn!(key; "Serving stale cached response after error: {}", e);
+                cache.touch(key).await;
+                Ok(value)
+            }
+            Err(e) => Err(e),
         }
-        
-        // Try to parse JSON from text
-        let jikan_response: JikanResponse = serde_json::from_str(&text)?;
-        Ok(jikan_response.data)
     }
-    
-    
+
+    async fn get_uncached_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, JikanError> {
+        let text = match self.fetch_text(url, None).await? {
+            FetchOutcome::Modified { body, .. } => body,
+            FetchOutcome::NotModified => String::new(),
+        };
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    async fn fetch_text(&self, url: &str, etag: Option<&str>) -> Result<FetchOutcome, JikanError> {
+        let request = TransportRequest {
+            url: url.to_string(),
+            etag: etag.map(|etag| etag.to_string()),
+        };
+
+        let mut attempt = 0;
+        let response = loop {
+            RateLimiter::shared().acquire().await;
+
+            debug!(url; "Requesting");
+            let response = self.transport.send(&request).await?;
+            let status = response.status;
+            debug!(url, status = status.as_u16(); "Response received");
+
+            if status.is_success() {
+                break response;
+            }
+
+            if status == StatusCode::NOT_MODIFIED {
+                return Ok(FetchOutcome::NotModified);
+            }
+
+            let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if retryable && attempt < MAX_RETRIES {
+                let delay = Self::retry_after(&response).unwrap_or_else(|| Self::backoff(attempt));
+                attempt += 1;
+                info!(url, status = status.as_u16(); "Retrying in {:?} (attempt {} of {})", delay, attempt, MAX_RETRIES);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                return Err(JikanError::RateLimited);
+            }
+
+            return Err(JikanError::Http { status, body: response.body });
+        };
+
+        let etag = response.header(reqwest::header::ETAG).map(|value| value.to_string());
+        let text = response.body;
+        if log::log_enabled!(log::Level::Trace) {
+            trace!(url, length = text.len(); "Response body: {}", Self::preview(&text));
+        }
+
+        Ok(FetchOutcome::Modified { body: text, etag })
+    }
+
+    // The start of a response body, cut at a character boundary
+    fn preview(text: &str) -> &str {
+        let end = (0..=BODY_PREVIEW_BYTES.min(text.len()))
+            .rev()
+            .find(|&end| text.is_char_boundary(end))
+            .unwrap_or(0);
+        &text[..end]
+    }
+
+    fn can_serve_stale(error: &JikanError) -> bool {
+        match error {
+            JikanError::Network(_) | JikanError::Transport(_) | JikanError::RateLimited => true,
+            JikanError::Http { status, .. } => status.is_server_error(),
+            JikanError::JsonParsing(_) | JikanError::InvalidRequest(_) => false,
+        }
+    }
+
+    // Exponential backoff: 500ms, 1s, 2s, ...
+    fn backoff(attempt: u32) -> Duration {
+        INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF)
+    }
+
+    // Only the delay-seconds form of Retry-After is honoured; HTTP dates fall back to backoff
+    fn retry_after(response: &TransportResponse) -> Option<Duration> {
+        let seconds = response
+            .header(reqwest::header::RETRY_AFTER)?
+            .trim()
+            .parse::<u64>()
+            .ok()?;
+        Some(Duration::from_secs(seconds).min(MAX_BACKOFF))
+    }
+}
+
+impl Default for JikanClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
@@ -1 +1,7 @@
-pub mod jikan;
\ No newline at end of file
+pub mod client_builder;
+pub mod jikan;
+pub mod rate_limiter;
+pub mod response_cache;
+pub mod search_query;
+pub mod image_cache;
+pub mod transport;
\ No newline at end of file
@@ -0,0 +1,93 @@
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// Jikan allows roughly 3 requests per second and 60 per minute
+const PER_SECOND_CAPACITY: f64 = 3.0;
+const PER_MINUTE_CAPACITY: f64 = 60.0;
+
+static SHARED_LIMITER: LazyLock<RateLimiter> = LazyLock::new(|| {
+    RateLimiter::new(vec![
+        TokenBucket::new(PER_SECOND_CAPACITY, Duration::from_secs(1)),
+        TokenBucket::new(PER_MINUTE_CAPACITY, Duration::from_secs(60)),
+    ])
+});
+
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, period: Duration) -> Self {
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / period.as_secs_f64(),
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn time_until_available(&self) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.refill_per_sec)
+        }
+    }
+}
+
+// A set of token buckets that must all have a token before a request may go out
+pub struct RateLimiter {
+    buckets: Mutex<Vec<TokenBucket>>,
+}
+
+impl RateLimiter {
+    fn new(buckets: Vec<TokenBucket>) -> Self {
+        Self {
+            buckets: Mutex::new(buckets),
+        }
+    }
+
+    // The limiter shared by every JikanClient in the process
+    pub fn shared() -> &'static RateLimiter {
+        &SHARED_LIMITER
+    }
+
+    // Waits until every bucket has a token, then takes one from each
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                for bucket in buckets.iter_mut() {
+                    bucket.refill(now);
+                }
+
+                let wait = buckets
+                    .iter()
+                    .map(TokenBucket::time_until_available)
+                    .max()
+                    .unwrap_or(Duration::ZERO);
+
+                if wait.is_zero() {
+                    for bucket in buckets.iter_mut() {
+                        bucket.tokens -= 1.0;
+                    }
+                    return;
+                }
+
+                wait
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task;
+
+const CACHE_DIR: &str = "waifu-viewer";
+const RESPONSES_DIR: &str = "responses";
+const DEFAULT_MAX_BYTES: u64 = 50 * 1024 * 1024;
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+// Bytes of entries in each cache directory, shared by every cache opened on it. A
+// directory is scanned on its first store and only counted after that.
+static TRACKED_SIZES: LazyLock<Mutex<HashMap<PathBuf, u64>>> = LazyLock::new(Default::default);
+
+// A cached response body together with what is needed to revalidate it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub key: String,
+    pub etag: Option<String>,
+    // Seconds since the Unix epoch when the body was last fetched or revalidated
+    pub fetched_at: u64,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub fn new(key: &str, etag: Option<String>, body: String) -> Self {
+        Self {
+            key: key.to_string(),
+            etag,
+            fetched_at: unix_now(),
+            body,
+        }
+    }
+
+    // Resets the entry's age after the server confirmed it is still current
+    pub fn mark_revalidated(&mut self) {
+        self.fetched_at = unix_now();
+    }
+}
+
+// On-disk cache of Jikan responses keyed by endpoint and query, with per-endpoint TTLs
+// and least-recently-used eviction once the directory grows past `max_bytes`
+pub struct ResponseCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    default_ttl: Duration,
+    // Endpoint prefixes (e.g. "/top/") and their TTLs; the longest matching prefix wins
+    ttls: Vec<(String, Duration)>,
+}
+
+impl ResponseCache {
+    // Opens the cache under the user's cache directory, if there is one
+    pub fn new() -> Option<Self> {
+        let mut dir = dirs::cache_dir()?;
+        dir.push(CACHE_DIR);
+        dir.push(RESPONSES_DIR);
+        Some(Self::with_dir(dir, DEFAULT_MAX_BYTES))
+    }
+
+    pub fn with_dir(dir: PathBuf, max_bytes: u64) -> Self {
+        let mut cache = Self {
+            dir,
+            max_bytes,
+            default_ttl: DEFAULT_TTL,
+            ttls: Vec::new(),
+        };
+        cache.set_ttl("/top/", Duration::from_secs(6 * 60 * 60));
+        cache.set_ttl("/characters?", Duration::from_secs(30 * 60));
+        cache.set_ttl("/characters/", Duration::from_secs(24 * 60 * 60));
+        cache
+    }
+
+    pub fn set_ttl(&mut self, endpoint_prefix: &str, ttl: Duration) {
+        self.ttls.retain(|(prefix, _)| prefix != endpoint_prefix);
+        self.ttls.push((endpoint_prefix.to_string(), ttl));
+    }
+
+    pub fn set_default_ttl(&mut self, ttl: Duration) {
+        self.default_ttl = ttl;
+    }
+
+    pub fn set_max_bytes(&mut self, max_bytes: u64) {
+        self.max_bytes = max_bytes;
+    }
+
+    pub fn ttl_for(&self, key: &str) -> Duration {
+        self.ttls
+            .iter()
+            .filter(|(prefix, _)| key.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, ttl)| *ttl)
+            .unwrap_or(self.default_ttl)
+    }
+
+    pub fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        let age = unix_now().saturating_sub(entry.fetched_at);
+        age < self.ttl_for(&entry.key).as_secs()
+    }
+
+    // Reads on a blocking thread, like every other file access of the cache
+    pub async fn load(&self, key: &str) -> Option<CacheEntry> {
+        let path = self.entry_path(key);
+        let key = key.to_string();
+        task::spawn_blocking(move || {
+            let contents = fs::read_to_string(path).ok()?;
+            let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+            // Guard against hash collisions between different keys
+            (entry.key == key).then_some(entry)
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    // Marks an entry as recently used so eviction keeps it around longer
+    pub async fn touch(&self, key: &str) {
+        let path = self.entry_path(key);
+        let _ = task::spawn_blocking(move || touch_file(&path)).await;
+    }
+
+    // Writes on a blocking thread, evicting only once the directory's tracked size passes
+    // `max_bytes`
+    pub async fn store(&self, entry: &CacheEntry) -> Result<(), String> {
+        let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        let dir = self.dir.clone();
+        let path = self.entry_path(&entry.key);
+        let max_bytes = self.max_bytes;
+
+        task::spawn_blocking(move || write_tracked(&dir, &path, json.as_bytes(), max_bytes, "json"))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        lock_sizes().remove(&self.dir);
+        match fs::remove_dir_all(&self.dir) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a_hash(key)))
+    }
+}
+
+fn lock_sizes() -> std::sync::MutexGuard<'static, HashMap<PathBuf, u64>> {
+    TRACKED_SIZES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Writes `contents` to `path` in `dir` atomically and keeps the directory's tracked size,
+// evicting least recently used files once it passes `max_bytes`. Blocking.
+pub(crate) fn write_tracked(dir: &Path, path: &Path, contents: &[u8], max_bytes: u64, extension: &str) -> Result<(), String> {
+    // Held across the write so concurrent stores keep the count exact
+    let mut sizes = lock_sizes();
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let replaced = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, path).map_err(|e| e.to_string())?;
+
+    let total = sizes
+        .get(dir)
+        .map(|total| total.saturating_sub(replaced) + contents.len() as u64);
+    let total = match total {
+        Some(total) if total <= max_bytes => total,
+        _ => evict_lru(dir, max_bytes, extension),
+    };
+    sizes.insert(dir.to_path_buf(), total);
+    Ok(())
+}
+
+// Removes the least recently modified files with `extension` until `dir` fits in `max_bytes`,
+// returning the size of what is left
+pub(crate) fn evict_lru(dir: &Path, max_bytes: u64, extension: &str) -> u64 {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut entries: Vec<(PathBuf, u64, SystemTime)> = read_dir
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == extension))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            Some((entry.path(), metadata.len(), modified))
+        })
+        .collect();
+
+    let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return total;
+    }
+
+    entries.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(size);
+        }
+    }
+    total
+}
+
+// Bumps a file's modification time so `evict_lru` treats it as recently used
+pub(crate) fn touch_file(path: &Path) {
+    if let Ok(file) = File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+// FNV-1a is used instead of `DefaultHasher` because file names must stay stable across builds
+pub(crate) fn fnv1a_hash(key: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    key.bytes().fold(OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+// Largest page size Jikan accepts
+pub const MAX_LIMIT: u32 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterOrderBy {
+    Favorites,
+    Name,
+    MalId,
+}
+
+impl CharacterOrderBy {
+    pub const ALL: [CharacterOrderBy; 3] = [CharacterOrderBy::Favorites, CharacterOrderBy::Name, CharacterOrderBy::MalId];
+
+    // The value Jikan expects for `order_by`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CharacterOrderBy::Favorites => "favorites",
+            CharacterOrderBy::Name => "name",
+            CharacterOrderBy::MalId => "mal_id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub const ALL: [SortDirection; 2] = [SortDirection::Asc, SortDirection::Desc];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        }
+    }
+}
+
+// Parameters for `/characters`, serialized as URL query parameters. Unset fields are
+// left out so Jikan applies its own defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterSearchQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order_by: Option<CharacterOrderBy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort: Option<SortDirection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    letter: Option<String>,
+}
+
+impl CharacterSearchQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Searches names for `text`; blank text clears the search term
+    pub fn query(mut self, text: &str) -> Self {
+        let text = text.trim();
+        self.q = (!text.is_empty()).then(|| text.to_string());
+        self
+    }
+
+    pub fn page(mut self, page: u32) -> Self {
+        self.page = Some(page.max(1));
+        self
+    }
+
+    // Results per page, clamped to what Jikan allows
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit.clamp(1, MAX_LIMIT));
+        self
+    }
+
+    pub fn order_by(mut self, order_by: CharacterOrderBy) -> Self {
+        self.order_by = Some(order_by);
+        self
+    }
+
+    pub fn sort(mut self, sort: SortDirection) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    // Only characters whose name starts with `letter`
+    pub fn letter(mut self, letter: char) -> Self {
+        self.letter = Some(letter.to_string());
+        self
+    }
+
+    pub fn text(&self) -> Option<&str> {
+        self.q.as_deref()
+    }
+
+    pub fn starting_letter(&self) -> Option<&str> {
+        self.letter.as_deref()
+    }
+
+    pub fn ordering(&self) -> Option<CharacterOrderBy> {
+        self.order_by
+    }
+
+    pub fn direction(&self) -> Option<SortDirection> {
+        self.sort
+    }
+
+    pub fn page_size(&self) -> Option<u32> {
+        self.limit
+    }
+
+    pub fn current_page(&self) -> u32 {
+        self.page.unwrap_or(1)
+    }
+
+    // Whether the query narrows results at all; Jikan lists every character otherwise
+    pub fn has_criteria(&self) -> bool {
+        self.q.is_some() || self.letter.is_some()
+    }
+}
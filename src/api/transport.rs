@@ -0,0 +1,71 @@
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::api::jikan::JikanError;
+
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<TransportResponse, JikanError>> + Send + 'a>>;
+
+// A GET request as the client issues it; `etag` becomes If-None-Match
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub url: String,
+    pub etag: Option<String>,
+}
+
+// Whatever came back, successful or not. Retries, caching and error mapping are the
+// client's job, so a transport only fails when no response arrived at all.
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn header(&self, name: impl reqwest::header::AsHeaderName) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+}
+
+// How JikanClient talks to the network. Swap it out to serve canned responses in
+// tests or to record what the app asks for.
+pub trait JikanTransport: Send + Sync {
+    fn send<'a>(&'a self, request: &'a TransportRequest) -> TransportFuture<'a>;
+}
+
+// The real thing: plain HTTP through a configured reqwest client
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl JikanTransport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a TransportRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let mut builder = self.client.get(&request.url);
+            if let Some(etag) = &request.etag {
+                builder = builder.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            let response = builder.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.text().await?;
+            Ok(TransportResponse { status, headers, body })
+        })
+    }
+}
@@ -0,0 +1,422 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use waifu_viewer::api::jikan::JikanClient;
+use waifu_viewer::api::search_query::{CharacterOrderBy, CharacterSearchQuery, SortDirection};
+use waifu_viewer::models::character::Character;
+use waifu_viewer::models::favorite::FavoriteRecord;
+use waifu_viewer::storage::favorites::FavoritesStorage;
+use waifu_viewer::storage::transfer::{ImportMode, TransferFormat};
+use waifu_viewer::utils::logger::{self, LogSettings};
+
+const USAGE: &str = "\
+Usage: waifu-cli [--json] <command> [arguments]
+
+Commands:
+  top [--page N]                 List the most favorited characters
+  search [query] [--page N] [--limit N] [--order-by O] [--sort S] [--letter L]
+                                 Search characters by name or starting letter
+                                 (O: favorites, name or mal_id; S: asc or desc)
+  show <id>                      Show everything about a character
+  fav list                       List saved favorites
+  fav add <id>...                Save characters to favorites
+  fav remove <id>...             Remove characters from favorites
+  export <file> [--format F]     Export favorites (F: json, csv or txt)
+  import <file> [--format F] [--replace]
+                                 Import favorites, merging unless --replace is given
+
+Options:
+  --json                         Print machine-readable JSON instead of tables
+  -h, --help                     Show this help
+
+Environment:
+  WAIFU_VIEWER_JIKAN_URL         Jikan base URL, e.g. a mirror or local mock server
+  WAIFU_VIEWER_TIMEOUT_SECS      Request timeout in seconds (default 30)
+  WAIFU_VIEWER_CONNECT_TIMEOUT_SECS
+                                 Connect timeout in seconds (default 10)
+  WAIFU_VIEWER_USER_AGENT        User-Agent header sent to Jikan
+  WAIFU_VIEWER_PROXY             Proxy URL for all requests
+  WAIFU_VIEWER_LOG               Log filter, e.g. debug or warn,waifu_viewer::api=trace
+
+The WAIFU_VIEWER_JIKAN_URL to WAIFU_VIEWER_PROXY variables override the same
+settings in ~/.config/waifu-viewer/jikan.json, a developer-only file. The app
+keeps its own network settings in its Preferences window.";
+
+// A failed command: usage mistakes exit with 2, everything else with 1
+enum CliError {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Failed(message)
+    }
+}
+
+type CliResult = Result<(), CliError>;
+
+struct Cli {
+    json: bool,
+    client: JikanClient,
+    storage: FavoritesStorage,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Diagnostics go to stderr only; the log file belongs to the app
+    logger::init(&LogSettings::default(), false);
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = take_flag(&mut args, "--json");
+
+    if args.is_empty() || take_flag(&mut args, "--help") || take_flag(&mut args, "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let cli = Cli {
+        json,
+        client: JikanClient::new(),
+        storage: FavoritesStorage::new(),
+    };
+
+    match cli.run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("waifu-cli: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("waifu-cli: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+impl Cli {
+    async fn run(&self, mut args: Vec<String>) -> CliResult {
+        let command = args.remove(0);
+        match command.as_str() {
+            "top" => {
+                let page = take_page(&mut args)?;
+                expect_no_more(&args)?;
+                let response = self.client.get_top_characters(page).await.map_err(|e| e.to_string())?;
+                self.print_characters(&response.data)
+            }
+            "search" => {
+                let query = take_search_query(&mut args)?;
+                let response = self.client.search_characters(&query).await.map_err(|e| e.to_string())?;
+                self.print_characters(&response.data)
+            }
+            "show" => {
+                let ids = parse_ids(&args)?;
+                let [mal_id] = ids[..] else {
+                    return Err(CliError::Usage("show takes exactly one character id".to_string()));
+                };
+                self.show(mal_id).await
+            }
+            "fav" => {
+                if args.is_empty() {
+                    return Err(CliError::Usage("fav needs list, add or remove".to_string()));
+                }
+                let action = args.remove(0);
+                match action.as_str() {
+                    "list" => {
+                        expect_no_more(&args)?;
+                        self.list_favorites()
+                    }
+                    "add" => self.add_favorites(&parse_ids(&args)?).await,
+                    "remove" => self.remove_favorites(&parse_ids(&args)?).await,
+                    _ => Err(CliError::Usage(format!("unknown fav action: {}", action))),
+                }
+            }
+            "export" => {
+                let format = take_format(&mut args)?;
+                let path = take_path(&args)?;
+                let format = format.unwrap_or_else(|| TransferFormat::from_path(&path));
+                let count = self.storage.export(&path, format).await?;
+                self.print_summary(&serde_json::json!({ "exported": count }), &format!("Exported {} favorites to {}", count, path.display()))
+            }
+            "import" => {
+                let format = take_format(&mut args)?;
+                let mode = if take_flag(&mut args, "--replace") { ImportMode::Replace } else { ImportMode::Merge };
+                let path = take_path(&args)?;
+                let format = format.unwrap_or_else(|| TransferFormat::from_path(&path));
+                let report = self.storage.import(&path, format, mode).await?;
+                self.print_summary(
+                    &serde_json::json!({
+                        "added": report.added,
+                        "skipped": report.skipped,
+                        "failed": report.failed,
+                        "failed_images": report.failed_images,
+                    }),
+                    &format!(
+                        "Imported {} favorites ({} skipped, {} could not be fetched)",
+                        report.added, report.skipped, report.failed
+                    ),
+                )
+            }
+            _ => Err(CliError::Usage(format!("unknown command: {}", command))),
+        }
+    }
+
+    async fn show(&self, mal_id: u32) -> CliResult {
+        let full = self.client.get_character_full(mal_id).await.map_err(|e| e.to_string())?;
+        if self.json {
+            return print_json(&full);
+        }
+
+        let character = &full.character;
+        println!("{} (#{})", character.name, character.mal_id);
+        if let Some(name_kanji) = character.name_kanji.as_deref().filter(|name| !name.is_empty()) {
+            println!("{}", name_kanji);
+        }
+        if !character.nicknames.is_empty() {
+            println!("Also known as: {}", character.nicknames.join(", "));
+        }
+        println!("Favorited by {} members", character.favorites);
+        println!("{}", character.url);
+
+        if let Some(about) = character.about.as_deref().filter(|about| !about.trim().is_empty()) {
+            println!("\n{}", about.trim());
+        }
+
+        let sections = [
+            ("Anime", full.anime.iter().map(|entry| vec![entry.anime.title.clone(), entry.role.clone()]).collect::<Vec<_>>()),
+            ("Manga", full.manga.iter().map(|entry| vec![entry.manga.title.clone(), entry.role.clone()]).collect()),
+            ("Voice Actors", full.voices.iter().map(|voice| vec![voice.person.name.clone(), voice.language.clone()]).collect()),
+        ];
+        for (title, rows) in sections {
+            if !rows.is_empty() {
+                println!("\n{}", title);
+                print_table(&[], &rows);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn list_favorites(&self) -> CliResult {
+        let favorites = self.storage.get_favorites()?;
+        if self.json {
+            return print_json(&favorites);
+        }
+
+        let rows: Vec<Vec<String>> = favorites
+            .iter()
+            .map(|record| {
+                let mut row = character_row(&record.character);
+                row.push(record.rating.map(|rating| rating.to_string()).unwrap_or_default());
+                row
+            })
+            .collect();
+        print_table(&["ID", "NAME", "KANJI", "FAVORITES", "RATING"], &rows);
+        Ok(())
+    }
+
+    async fn add_favorites(&self, ids: &[u32]) -> CliResult {
+        let mut added = Vec::new();
+        for &mal_id in ids {
+            let character = self.client.get_character(mal_id).await.map_err(|e| format!("character {}: {}", mal_id, e))?;
+            self.storage.add_favorite(character.clone()).await?;
+            if !self.json {
+                println!("Added {} (#{})", character.name, mal_id);
+            }
+            added.push(character);
+        }
+
+        if self.json {
+            print_json(&added)?;
+        }
+        Ok(())
+    }
+
+    async fn remove_favorites(&self, ids: &[u32]) -> CliResult {
+        let mut removed: Vec<FavoriteRecord> = Vec::new();
+        for &mal_id in ids {
+            let record = self
+                .storage
+                .get_favorite(mal_id)?
+                .ok_or_else(|| format!("character {} is not a favorite", mal_id))?;
+            self.storage.remove_favorite(record.character.clone()).await?;
+            if !self.json {
+                println!("Removed {} (#{})", record.character.name, mal_id);
+            }
+            removed.push(record);
+        }
+
+        if self.json {
+            print_json(&removed)?;
+        }
+        Ok(())
+    }
+
+    fn print_characters(&self, characters: &[Character]) -> CliResult {
+        if self.json {
+            return print_json(&characters);
+        }
+
+        let rows: Vec<Vec<String>> = characters.iter().map(character_row).collect();
+        print_table(&["ID", "NAME", "KANJI", "FAVORITES"], &rows);
+        Ok(())
+    }
+
+    fn print_summary(&self, json: &serde_json::Value, text: &str) -> CliResult {
+        if self.json {
+            return print_json(json);
+        }
+        println!("{}", text);
+        Ok(())
+    }
+}
+
+fn character_row(character: &Character) -> Vec<String> {
+    vec![
+        character.mal_id.to_string(),
+        character.name.clone(),
+        character.name_kanji.clone().unwrap_or_default(),
+        character.favorites.to_string(),
+    ]
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> CliResult {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", json);
+    Ok(())
+}
+
+// Prints left-aligned columns padded to the widest cell; an empty header prints rows only
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let columns = header.len().max(rows.iter().map(Vec::len).max().unwrap_or(0));
+    let mut widths = vec![0; columns];
+    let header: Vec<String> = header.iter().map(|title| title.to_string()).collect();
+    for row in std::iter::once(&header).chain(rows) {
+        for (column, cell) in row.iter().enumerate() {
+            widths[column] = widths[column].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(column, cell)| format!("{:<width$}", cell, width = widths[column]))
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+
+    if !header.is_empty() {
+        println!("{}", format_row(&header));
+    }
+    for row in rows {
+        println!("{}", format_row(row));
+    }
+}
+
+// Removes `flag` from the arguments, returning whether it was present
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != before
+}
+
+// Removes `option` and the value following it from the arguments
+fn take_option(args: &mut Vec<String>, option: &str) -> Result<Option<String>, CliError> {
+    let Some(position) = args.iter().position(|arg| arg == option) else {
+        return Ok(None);
+    };
+    if position + 1 >= args.len() {
+        return Err(CliError::Usage(format!("{} needs a value", option)));
+    }
+    let value = args.remove(position + 1);
+    args.remove(position);
+    Ok(Some(value))
+}
+
+fn take_page(args: &mut Vec<String>) -> Result<u32, CliError> {
+    match take_option(args, "--page")? {
+        Some(page) => page
+            .parse::<u32>()
+            .ok()
+            .filter(|&page| page > 0)
+            .ok_or_else(|| CliError::Usage(format!("invalid page: {}", page))),
+        None => Ok(1),
+    }
+}
+
+// Builds a search from the remaining words and the search options
+fn take_search_query(args: &mut Vec<String>) -> Result<CharacterSearchQuery, CliError> {
+    let mut query = CharacterSearchQuery::new().page(take_page(args)?);
+
+    if let Some(limit) = take_option(args, "--limit")? {
+        let limit = limit
+            .parse::<u32>()
+            .map_err(|_| CliError::Usage(format!("invalid limit: {}", limit)))?;
+        query = query.limit(limit);
+    }
+    if let Some(order_by) = take_option(args, "--order-by")? {
+        let order_by = CharacterOrderBy::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == order_by)
+            .ok_or_else(|| CliError::Usage(format!("unknown order: {}", order_by)))?;
+        query = query.order_by(order_by);
+    }
+    if let Some(sort) = take_option(args, "--sort")? {
+        let sort = SortDirection::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == sort)
+            .ok_or_else(|| CliError::Usage(format!("unknown sort direction: {}", sort)))?;
+        query = query.sort(sort);
+    }
+    if let Some(letter) = take_option(args, "--letter")? {
+        let mut chars = letter.chars();
+        let (Some(first), None) = (chars.next(), chars.next()) else {
+            return Err(CliError::Usage(format!("--letter takes a single letter, not {}", letter)));
+        };
+        query = query.letter(first);
+    }
+
+    query = query.query(&args.join(" "));
+    if !query.has_criteria() {
+        return Err(CliError::Usage("search needs a query or --letter".to_string()));
+    }
+    Ok(query)
+}
+
+fn take_format(args: &mut Vec<String>) -> Result<Option<TransferFormat>, CliError> {
+    let Some(format) = take_option(args, "--format")? else {
+        return Ok(None);
+    };
+    TransferFormat::ALL
+        .into_iter()
+        .find(|candidate| candidate.extension() == format.to_lowercase())
+        .map(Some)
+        .ok_or_else(|| CliError::Usage(format!("unknown format: {} (expected json, csv or txt)", format)))
+}
+
+fn take_path(args: &[String]) -> Result<PathBuf, CliError> {
+    match args {
+        [path] => Ok(PathBuf::from(path)),
+        [] => Err(CliError::Usage("a file path is required".to_string())),
+        _ => Err(CliError::Usage(format!("unexpected arguments: {}", args[1..].join(" ")))),
+    }
+}
+
+fn parse_ids(args: &[String]) -> Result<Vec<u32>, CliError> {
+    if args.is_empty() {
+        return Err(CliError::Usage("at least one character id is required".to_string()));
+    }
+    args.iter()
+        .map(|arg| arg.parse::<u32>().map_err(|_| CliError::Usage(format!("invalid character id: {}", arg))))
+        .collect()
+}
+
+fn expect_no_more(args: &[String]) -> CliResult {
+    if args.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Usage(format!("unexpected arguments: {}", args.join(" "))))
+    }
+}
@@ -1,7 +1,10 @@
 use libadwaita as adw;
 use adw::prelude::*;
 use glib::Bytes;
+use log::warn;
+use waifu_viewer::ui::utils::app_settings::{AppSettings, APP_ID};
 use waifu_viewer::ui::window::WaifuWindow;
+use waifu_viewer::utils::logger::{self, LogSettings};
 
 mod resources {
     pub static COMPILED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/compiled.gresource"));
@@ -9,11 +12,14 @@ mod resources {
 
 #[tokio::main]
 async fn main() {
+    // Verbose logging is switched on once the preferences are loaded
+    logger::init(&LogSettings::default(), true);
+
     // Cek kalau dijalankan sebagai root
     #[cfg(unix)]
     {
         if nix::unistd::Uid::effective().is_root() {
-            eprintln!("onichan baka ngapain jalan aplikasi ini pakek root atau sudo ini cuman aplikasi biasa");
+            warn!("onichan baka ngapain jalan aplikasi ini pakek root atau sudo ini cuman aplikasi biasa");
             // Optional: exit biar ga lanjut
             // std::process::exit(1);
         }
@@ -25,9 +31,10 @@ async fn main() {
     let data = Bytes::from_static(resources::COMPILED);
     let resource = gio::Resource::from_data(&data).expect("Failed to create resource from data");
     gio::resources_register(&resource);
+    AppSettings::shared().apply();
 
     let app = adw::Application::builder()
-        .application_id("com.example.WaifuViewer")
+        .application_id(APP_ID)
         .build();
 
     app.connect_activate(|app| {
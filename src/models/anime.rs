@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::character::CharacterImages;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anime {
+    #[serde(rename = "mal_id")]
+    pub mal_id: u32,
+
+    #[serde(rename = "url")]
+    pub url: String,
+
+    // Same shape as a character's images; only the JPG variants are used
+    #[serde(rename = "images")]
+    pub images: CharacterImages,
+
+    #[serde(rename = "title")]
+    pub title: String,
+
+    #[serde(rename = "title_english", default)]
+    pub title_english: Option<String>,
+
+    #[serde(rename = "title_japanese", default)]
+    pub title_japanese: Option<String>,
+
+    // "TV", "Movie", "OVA", ...
+    #[serde(rename = "type", default)]
+    pub media_type: Option<String>,
+
+    #[serde(rename = "episodes", default)]
+    pub episodes: Option<u32>,
+
+    #[serde(rename = "status", default)]
+    pub status: Option<String>,
+
+    #[serde(rename = "score", default)]
+    pub score: Option<f64>,
+
+    #[serde(rename = "year", default)]
+    pub year: Option<u32>,
+
+    #[serde(rename = "synopsis", default)]
+    pub synopsis: Option<String>,
+}
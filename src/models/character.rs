@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CharacterImages {
     pub jpg: CharacterImageJpg,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CharacterImageJpg {
     #[serde(rename = "image_url")]
     pub image_url: Option<String>,
@@ -14,7 +14,31 @@ pub struct CharacterImageJpg {
     pub small_image_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Entry of `/characters/{id}/pictures`. Jikan has served both a `jpg` object and bare
+// URL fields here, so either is accepted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterPicture {
+    #[serde(rename = "jpg", default)]
+    pub jpg: Option<CharacterImageJpg>,
+
+    #[serde(rename = "image_url", default)]
+    pub image_url: Option<String>,
+
+    #[serde(rename = "large_image_url", default)]
+    pub large_image_url: Option<String>,
+}
+
+impl CharacterPicture {
+    // The largest variant available
+    pub fn url(&self) -> Option<&str> {
+        self.large_image_url
+            .as_deref()
+            .or(self.image_url.as_deref())
+            .or(self.jpg.as_ref().and_then(|jpg| jpg.image_url.as_deref()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Character {
     #[serde(rename = "mal_id")]
     pub mal_id: u32,
@@ -39,4 +63,135 @@ pub struct Character {
     
     #[serde(rename = "about")]
     pub about: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaEntry {
+    #[serde(rename = "mal_id")]
+    pub mal_id: u32,
+
+    #[serde(rename = "url")]
+    pub url: String,
+
+    #[serde(rename = "title")]
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterAnimeRole {
+    #[serde(rename = "role")]
+    pub role: String,
+
+    #[serde(rename = "anime")]
+    pub anime: MediaEntry,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterMangaRole {
+    #[serde(rename = "role")]
+    pub role: String,
+
+    #[serde(rename = "manga")]
+    pub manga: MediaEntry,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonEntry {
+    #[serde(rename = "mal_id")]
+    pub mal_id: u32,
+
+    #[serde(rename = "url")]
+    pub url: String,
+
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterVoice {
+    #[serde(rename = "language")]
+    pub language: String,
+
+    #[serde(rename = "person")]
+    pub person: PersonEntry,
+}
+
+// Payload of `/characters/{id}/full`: the base character plus its appearances and voice actors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterFull {
+    #[serde(flatten)]
+    pub character: Character,
+
+    #[serde(rename = "anime", default)]
+    pub anime: Vec<CharacterAnimeRole>,
+
+    #[serde(rename = "manga", default)]
+    pub manga: Vec<CharacterMangaRole>,
+
+    #[serde(rename = "voices", default)]
+    pub voices: Vec<CharacterVoice>,
+}
+
+// A character as listed in a series cast, which carries fewer fields than `Character`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastCharacter {
+    #[serde(rename = "mal_id")]
+    pub mal_id: u32,
+
+    #[serde(rename = "url")]
+    pub url: String,
+
+    #[serde(rename = "images")]
+    pub images: CharacterImages,
+
+    #[serde(rename = "name")]
+    pub name: String,
+}
+
+impl CastCharacter {
+    // A `Character` for cards; the fields a cast listing lacks are left empty, so cards
+    // built from it fetch the full character before saving a favorite
+    pub fn to_character(&self) -> Character {
+        Character {
+            mal_id: self.mal_id,
+            url: self.url.clone(),
+            images: self.images.clone(),
+            name: self.name.clone(),
+            name_kanji: None,
+            nicknames: Vec::new(),
+            favorites: 0,
+            about: None,
+        }
+    }
+}
+
+// Entry of `/anime/{id}/characters` and `/manga/{id}/characters`. Manga casts have no
+// favorites count or voice actors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastMember {
+    #[serde(rename = "character")]
+    pub character: CastCharacter,
+
+    // "Main" or "Supporting"
+    #[serde(rename = "role")]
+    pub role: String,
+
+    #[serde(rename = "favorites", default)]
+    pub favorites: u32,
+
+    #[serde(rename = "voice_actors", default)]
+    pub voice_actors: Vec<CharacterVoice>,
+}
+
+impl CastMember {
+    pub fn to_character(&self) -> Character {
+        Character {
+            favorites: self.favorites,
+            ..self.character.to_character()
+        }
+    }
+
+    pub fn is_main(&self) -> bool {
+        self.role.eq_ignore_ascii_case("main")
+    }
+}
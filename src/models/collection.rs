@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+// A user-named collection of saved characters, e.g. "seasonal" or "to watch"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomList {
+    #[serde(rename = "id")]
+    pub id: u32,
+
+    #[serde(rename = "name")]
+    pub name: String,
+
+    // MAL ids of the characters in the list, in the order they were added
+    #[serde(rename = "members")]
+    pub members: Vec<u32>,
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::character::Character;
+
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 10;
+
+// A saved character together with the user's own data about it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavoriteRecord {
+    // The Jikan payload as of the last save or re-sync
+    #[serde(rename = "character")]
+    pub character: Character,
+
+    // Personal rating from MIN_RATING to MAX_RATING, if one was given
+    #[serde(rename = "rating")]
+    pub rating: Option<u8>,
+
+    // Free-form notes in Markdown
+    #[serde(rename = "notes", default)]
+    pub notes: String,
+
+    // Seconds since the Unix epoch
+    #[serde(rename = "added_at")]
+    pub added_at: u64,
+
+    #[serde(rename = "updated_at")]
+    pub updated_at: u64,
+
+    // Gallery picture chosen for the card instead of Jikan's default image
+    #[serde(rename = "thumbnail_url", default)]
+    pub thumbnail_url: Option<String>,
+}
+
+impl FavoriteRecord {
+    pub fn new(character: Character) -> Self {
+        let now = unix_now();
+        Self {
+            character,
+            rating: None,
+            notes: String::new(),
+            added_at: now,
+            updated_at: now,
+            thumbnail_url: None,
+        }
+    }
+
+    pub fn mal_id(&self) -> u32 {
+        self.character.mal_id
+    }
+
+    // The picture shown on the card: the chosen thumbnail, else Jikan's default image
+    pub fn portrait_url(&self) -> Option<&str> {
+        self.thumbnail_url
+            .as_deref()
+            .or(self.character.images.jpg.image_url.as_deref())
+    }
+
+    // Marks the record as changed just now
+    pub fn touch(&mut self) {
+        self.updated_at = unix_now();
+    }
+}
+
+pub fn validate_rating(rating: Option<u8>) -> Result<Option<u8>, String> {
+    match rating {
+        Some(value) if !(MIN_RATING..=MAX_RATING).contains(&value) => Err(format!(
+            "Rating must be between {} and {}",
+            MIN_RATING, MAX_RATING
+        )),
+        _ => Ok(rating),
+    }
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
\ No newline at end of file
@@ -1 +1,7 @@
-pub mod character;
\ No newline at end of file
+pub mod anime;
+pub mod character;
+pub mod collection;
+pub mod favorite;
+pub mod manga;
+pub mod person;
+pub mod saved_search;
\ No newline at end of file
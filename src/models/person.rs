@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::character::{CastCharacter, CharacterImages, MediaEntry};
+
+// A voice actor, or anyone else with a MyAnimeList staff page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Person {
+    #[serde(rename = "mal_id")]
+    pub mal_id: u32,
+
+    #[serde(rename = "url")]
+    pub url: String,
+
+    // Only `image_url` is set for people
+    #[serde(rename = "images")]
+    pub images: CharacterImages,
+
+    #[serde(rename = "name")]
+    pub name: String,
+
+    #[serde(rename = "given_name", default)]
+    pub given_name: Option<String>,
+
+    #[serde(rename = "family_name", default)]
+    pub family_name: Option<String>,
+
+    #[serde(rename = "alternate_names", default)]
+    pub alternate_names: Vec<String>,
+
+    // ISO 8601 timestamp
+    #[serde(rename = "birthday", default)]
+    pub birthday: Option<String>,
+
+    #[serde(rename = "favorites", default)]
+    pub favorites: u32,
+
+    #[serde(rename = "about", default)]
+    pub about: Option<String>,
+}
+
+// One character a person voiced in one anime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonVoiceRole {
+    // "Main" or "Supporting"
+    #[serde(rename = "role")]
+    pub role: String,
+
+    #[serde(rename = "anime")]
+    pub anime: MediaEntry,
+
+    #[serde(rename = "character")]
+    pub character: CastCharacter,
+}
+
+impl PersonVoiceRole {
+    pub fn is_main(&self) -> bool {
+        self.role.eq_ignore_ascii_case("main")
+    }
+}
+
+// Payload of `/people/{id}/full`; staff positions are not used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonFull {
+    #[serde(flatten)]
+    pub person: Person,
+
+    #[serde(rename = "voices", default)]
+    pub voices: Vec<PersonVoiceRole>,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::api::search_query::CharacterSearchQuery;
+
+// A search pinned to the Explore page, filters included
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    #[serde(rename = "name")]
+    pub name: String,
+
+    #[serde(rename = "query")]
+    pub query: CharacterSearchQuery,
+}
@@ -1,15 +1,43 @@
-use std::fs::{self, File};
-use std::io::{Read, Write};
-use std::path::PathBuf;
-use serde_json;
+use log::warn;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use tokio::task;
 
+use crate::api::jikan::JikanClient;
+use crate::api::search_query::CharacterSearchQuery;
 use crate::models::character::Character;
+use crate::models::collection::CustomList;
+use crate::models::favorite::FavoriteRecord;
+use crate::models::saved_search::SavedSearch;
+use crate::storage::store::{FavoritesIndex, FavoritesStore, RemovedFavorite};
+use crate::storage::transfer::{self, IdOnlyFavorite, ImportMode, TransferFormat};
 
 const FAVORITES_FILE: &str = "favorites.json";
+const PORTRAITS_DIR: &str = "portraits";
+
+// Outcome of refreshing the stored favorites from Jikan
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub updated: usize,
+    pub failed: usize,
+    pub failed_images: usize,
+}
+
+// Outcome of importing a collection file
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub added: usize,
+    // Characters that were already saved or appeared twice in the file
+    pub skipped: usize,
+    // ID-only entries whose character could not be fetched from Jikan
+    pub failed: usize,
+    pub failed_images: usize,
+}
 
 pub struct FavoritesStorage {
     file_path: PathBuf,
+    portraits_dir: PathBuf,
 }
 
 impl FavoritesStorage {
@@ -21,71 +49,374 @@ impl FavoritesStorage {
         } else {
             PathBuf::from(FAVORITES_FILE)
         };
+
+        // Portraits of favorited characters are kept so the collection works offline
+        let portraits_dir = if let Some(mut data_dir) = dirs::data_dir() {
+            data_dir.push("waifu-viewer");
+            data_dir.join(PORTRAITS_DIR)
+        } else {
+            PathBuf::from(PORTRAITS_DIR)
+        };
         
-        Self { file_path }
+        Self { file_path, portraits_dir }
     }
 
-    pub fn get_favorites(&self) -> Result<Vec<Character>, String> {
-        if !self.file_path.exists() {
-            return Ok(Vec::new());
-        }
+    // Storage backed by explicit locations instead of the user's config and data directories
+    pub fn with_paths(file_path: PathBuf, portraits_dir: PathBuf) -> Self {
+        Self { file_path, portraits_dir }
+    }
+
+    pub fn local_image_path(&self, mal_id: u32) -> PathBuf {
+        self.portraits_dir.join(format!("{}.jpg", mal_id))
+    }
 
-        let mut file = File::open(&self.file_path).map_err(|e| e.to_string())?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).map_err(|e| e.to_string())?;
+    // `file://` URI of the stored portrait, if one has been downloaded
+    pub fn local_image_uri(&self, mal_id: u32) -> Option<String> {
+        let path = self.local_image_path(mal_id);
+        path.exists().then(|| format!("file://{}", path.display()))
+    }
 
-        if contents.is_empty() {
-            return Ok(Vec::new());
-        }
+    pub fn get_favorites(&self) -> Result<Vec<FavoriteRecord>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.favorites().to_vec())
+    }
+
+    pub fn get_favorite(&self, mal_id: u32) -> Result<Option<FavoriteRecord>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.get(mal_id).cloned())
+    }
+
+    pub fn is_favorite(&self, mal_id: u32) -> Result<bool, String> {
+        FavoritesStore::read(&self.file_path, |index| index.contains(mal_id))
+    }
+
+    pub fn get_lists(&self) -> Result<Vec<CustomList>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.lists().to_vec())
+    }
+
+    // Saved characters in the list, in list order
+    pub fn get_list_members(&self, list_id: u32) -> Result<Vec<FavoriteRecord>, String> {
+        FavoritesStore::read(&self.file_path, |index| {
+            index
+                .list(list_id)
+                .map(|list| list.members.iter().filter_map(|&mal_id| index.get(mal_id).cloned()).collect())
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn get_lists_containing(&self, mal_id: u32) -> Result<Vec<u32>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.lists_containing(mal_id))
+    }
+
+    pub fn get_all_tags(&self) -> Result<Vec<String>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.all_tags())
+    }
+
+    pub fn get_tags(&self, mal_id: u32) -> Result<Vec<String>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.tags_for(mal_id).to_vec())
+    }
+
+    // Saved characters carrying `tag`, compared case-insensitively
+    pub fn get_tagged(&self, tag: &str) -> Result<Vec<FavoriteRecord>, String> {
+        FavoritesStore::read(&self.file_path, |index| {
+            index
+                .favorites()
+                .iter()
+                .filter(|record| {
+                    index
+                        .tags_for(record.mal_id())
+                        .iter()
+                        .any(|existing| existing.eq_ignore_ascii_case(tag))
+                })
+                .cloned()
+                .collect()
+        })
+    }
+
+    pub fn get_search_history(&self) -> Result<Vec<String>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.search_history().to_vec())
+    }
+
+    pub fn get_saved_searches(&self) -> Result<Vec<SavedSearch>, String> {
+        FavoritesStore::read(&self.file_path, |index| index.saved_searches().to_vec())
+    }
+
+    // Returns the updated history
+    pub async fn record_search(&self, text: &str) -> Result<Vec<String>, String> {
+        let text = text.to_string();
+        self.write(move |index| {
+            index.record_search(&text);
+            Ok(index.search_history().to_vec())
+        }).await
+    }
+
+    pub async fn remove_search(&self, text: &str) -> Result<Vec<String>, String> {
+        let text = text.to_string();
+        self.write(move |index| {
+            index.remove_search(&text);
+            Ok(index.search_history().to_vec())
+        }).await
+    }
+
+    pub async fn save_search(&self, name: &str, query: CharacterSearchQuery) -> Result<(), String> {
+        let name = name.to_string();
+        self.write(move |index| index.save_search(&name, query)).await
+    }
+
+    pub async fn remove_saved_search(&self, name: &str) -> Result<(), String> {
+        let name = name.to_string();
+        self.write(move |index| index.remove_saved_search(&name)).await
+    }
+
+    pub async fn create_list(&self, name: &str) -> Result<u32, String> {
+        let name = name.to_string();
+        self.write(move |index| index.create_list(&name)).await
+    }
+
+    pub async fn rename_list(&self, list_id: u32, name: &str) -> Result<(), String> {
+        let name = name.to_string();
+        self.write(move |index| index.rename_list(list_id, &name)).await
+    }
+
+    pub async fn delete_list(&self, list_id: u32) -> Result<(), String> {
+        self.write(move |index| index.delete_list(list_id)).await
+    }
+
+    pub async fn move_list(&self, list_id: u32, position: usize) -> Result<(), String> {
+        self.write(move |index| index.move_list(list_id, position)).await
+    }
+
+    pub async fn set_list_membership(&self, list_id: u32, mal_id: u32, member: bool) -> Result<(), String> {
+        self.write(move |index| index.set_list_membership(list_id, mal_id, member)).await
+    }
 
-        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    pub async fn set_tags(&self, mal_id: u32, tags: Vec<String>) -> Result<(), String> {
+        self.write(move |index| index.set_tags(mal_id, &tags)).await
+    }
+
+    pub async fn set_rating(&self, mal_id: u32, rating: Option<u8>) -> Result<(), String> {
+        self.write(move |index| index.set_rating(mal_id, rating)).await
+    }
+
+    pub async fn set_notes(&self, mal_id: u32, notes: &str) -> Result<(), String> {
+        let notes = notes.to_string();
+        self.write(move |index| index.set_notes(mal_id, &notes)).await
+    }
+
+    // Uses a gallery picture as the stored portrait, or Jikan's default image again with
+    // `None`. The choice is only saved once the picture has been downloaded.
+    pub async fn set_thumbnail(&self, mal_id: u32, thumbnail_url: Option<String>) -> Result<(), String> {
+        let record = self
+            .get_favorite(mal_id)?
+            .ok_or_else(|| format!("Character {} is not saved", mal_id))?;
+        let image_url = thumbnail_url
+            .as_deref()
+            .or(record.character.images.jpg.image_url.as_deref());
+        self.download_image(mal_id, image_url).await?;
+
+        self.write(move |index| index.set_thumbnail(mal_id, thumbnail_url)).await
     }
 
     pub async fn add_favorite(&self, character: Character) -> Result<(), String> {
         let file_path = self.file_path.clone();
+        let stored = character.clone();
+        let added = task::spawn_blocking(move || {
+            FavoritesStore::write(&file_path, |index| Ok(index.insert(stored)))
+        }).await.map_err(|e| e.to_string())??;
+
+        if !added && self.local_image_uri(character.mal_id).is_some() {
+            return Ok(());
+        }
+
+        // A missing portrait only means the card falls back to the remote image
+        let image_url = character.images.jpg.image_url.as_deref();
+        if let Err(e) = self.download_image(character.mal_id, image_url).await {
+            warn!("Failed to store portrait for {}: {}", character.mal_id, e);
+        }
+
+        Ok(())
+    }
+
+    // Returns what was removed, for `restore_favorite`
+    pub async fn remove_favorite(&self, character: Character) -> Result<Option<RemovedFavorite>, String> {
+        let file_path = self.file_path.clone();
+        let image_path = self.local_image_path(character.mal_id);
         task::spawn_blocking(move || {
-            let mut favorites = Self::load_favorites_sync(&file_path)?;
-            
-            if !favorites.iter().any(|c| c.mal_id == character.mal_id) {
-                favorites.push(character);
-                Self::save_favorites_sync(&file_path, &favorites)?;
+            let removed = FavoritesStore::write(&file_path, |index| Ok(index.remove(character.mal_id)))?;
+
+            match fs::remove_file(&image_path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(removed),
             }
-            
-            Ok::<(), String>(())
-        }).await.map_err(|e| e.to_string())?.map_err(|e: String| e)
+        }).await.map_err(|e| e.to_string())?
     }
 
-    pub async fn remove_favorite(&self, character: Character) -> Result<(), String> {
+    // Undoes `remove_favorite`, downloading the portrait again
+    pub async fn restore_favorite(&self, removed: RemovedFavorite) -> Result<(), String> {
+        let mal_id = removed.record.mal_id();
+        let image_url = removed.record.portrait_url().map(str::to_string);
+        let restored = self.write(move |index| Ok(index.restore(removed))).await?;
+
+        if !restored && self.local_image_uri(mal_id).is_some() {
+            return Ok(());
+        }
+        if let Err(e) = self.download_image(mal_id, image_url.as_deref()).await {
+            warn!("Failed to store portrait for {}: {}", mal_id, e);
+        }
+        Ok(())
+    }
+
+    // Refreshes every favorite's metadata and portrait from Jikan, bypassing the response cache
+    pub async fn resync(&self) -> Result<SyncReport, String> {
+        let favorites = self.get_favorites()?;
+        let client = JikanClient::new().with_cache(None);
+        let mut report = SyncReport::default();
+        let mut refreshed = Vec::new();
+
+        for record in favorites {
+            match client.get_character(record.mal_id()).await {
+                Ok(updated) => {
+                    // A chosen thumbnail is kept; only the default image follows Jikan
+                    let image_url = record
+                        .thumbnail_url
+                        .as_deref()
+                        .or(updated.images.jpg.image_url.as_deref());
+                    if let Err(e) = self.download_image(updated.mal_id, image_url).await {
+                        warn!("Failed to refresh portrait for {}: {}", updated.mal_id, e);
+                        report.failed_images += 1;
+                    }
+                    refreshed.push(updated);
+                    report.updated += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to refresh favorite {}: {}", record.mal_id(), e);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        // Merge into the current file so favorites changed during the sync are kept
         let file_path = self.file_path.clone();
         task::spawn_blocking(move || {
-            let mut favorites = Self::load_favorites_sync(&file_path)?;
-            
-            favorites.retain(|c| c.mal_id != character.mal_id);
-            
-            Self::save_favorites_sync(&file_path, &favorites)
-        }).await.map_err(|e| e.to_string())?.map_err(|e: String| e)
+            FavoritesStore::write(&file_path, |index| {
+                for updated in refreshed {
+                    index.update(updated);
+                }
+                Ok(())
+            })
+        }).await.map_err(|e| e.to_string())??;
+
+        Ok(report)
     }
 
-    fn load_favorites_sync(file_path: &PathBuf) -> Result<Vec<Character>, String> {
-        if !file_path.exists() {
-            return Ok(Vec::new());
+    // Writes the collection to `path`; returns how many characters were exported
+    pub async fn export(&self, path: &Path, format: TransferFormat) -> Result<usize, String> {
+        let (contents, count) = FavoritesStore::read(&self.file_path, |index| {
+            transfer::export(index.document(), format).map(|contents| (contents, index.favorites().len()))
+        })??;
+
+        tokio::fs::write(path, contents).await.map_err(|e| e.to_string())?;
+        Ok(count)
+    }
+
+    // Reads a collection file, fetches characters that only came with an id, and merges
+    // the result into the store or replaces it
+    pub async fn import(&self, path: &Path, format: TransferFormat, mode: ImportMode) -> Result<ImportReport, String> {
+        let contents = tokio::fs::read_to_string(path).await.map_err(|e| e.to_string())?;
+        let mut imported = transfer::parse(&contents, format)?;
+        let mut report = ImportReport::default();
+
+        // Only fetch what a merge would not skip anyway
+        let existing: HashSet<u32> = match mode {
+            ImportMode::Merge => self.get_favorites()?.iter().map(FavoriteRecord::mal_id).collect(),
+            ImportMode::Replace => HashSet::new(),
+        };
+        let client = JikanClient::new();
+        for entry in std::mem::take(&mut imported.id_only) {
+            if existing.contains(&entry.mal_id) {
+                report.skipped += 1;
+                continue;
+            }
+            match client.get_character(entry.mal_id).await {
+                Ok(character) => imported.document.favorites.push(Self::hydrate(entry, character)),
+                Err(e) => {
+                    warn!("Failed to fetch imported character {}: {}", entry.mal_id, e);
+                    report.failed += 1;
+                }
+            }
         }
 
-        let mut file = File::open(file_path).map_err(|e| e.to_string())?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents).map_err(|e| e.to_string())?;
+        let records = imported.document.favorites.clone();
+        let imported_count = records.len();
+        let (added, removed) = self.write(move |index| {
+            let before: HashSet<u32> = index.favorites().iter().map(FavoriteRecord::mal_id).collect();
+            let added = index.import(imported.document, mode);
+            let removed: Vec<u32> = before.into_iter().filter(|&mal_id| !index.contains(mal_id)).collect();
+            Ok((added, removed))
+        }).await?;
+
+        report.added = added.len();
+        report.skipped += imported_count - added.len();
 
-        if contents.is_empty() {
-            return Ok(Vec::new());
+        for mal_id in removed {
+            let _ = fs::remove_file(self.local_image_path(mal_id));
+        }
+        for record in records.iter().filter(|record| added.contains(&record.mal_id())) {
+            if let Err(e) = self.download_image(record.mal_id(), record.portrait_url()).await {
+                warn!("Failed to store portrait for {}: {}", record.mal_id(), e);
+                report.failed_images += 1;
+            }
         }
 
-        serde_json::from_str(&contents).map_err(|e| e.to_string())
+        Ok(report)
     }
 
-    fn save_favorites_sync(file_path: &PathBuf, favorites: &[Character]) -> Result<(), String> {
-        let json = serde_json::to_string_pretty(favorites).map_err(|e| e.to_string())?;
-        let mut file = File::create(file_path).map_err(|e| e.to_string())?;
-        file.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
-        Ok(())
+    fn hydrate(entry: IdOnlyFavorite, character: Character) -> FavoriteRecord {
+        let mut record = FavoriteRecord::new(character);
+        record.rating = entry.rating;
+        record.notes = entry.notes;
+        if let Some(added_at) = entry.added_at {
+            record.added_at = added_at;
+        }
+        record
+    }
+
+    // Runs a store transaction off the main thread
+    async fn write<R, F>(&self, f: F) -> Result<R, String>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut FavoritesIndex) -> Result<R, String> + Send + 'static,
+    {
+        let file_path = self.file_path.clone();
+        task::spawn_blocking(move || FavoritesStore::write(&file_path, f))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    async fn download_image(&self, mal_id: u32, image_url: Option<&str>) -> Result<(), String> {
+        let Some(image_url) = image_url else {
+            return Ok(());
+        };
+
+        let bytes = reqwest::get(image_url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .bytes()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let portraits_dir = self.portraits_dir.clone();
+        let image_path = self.local_image_path(mal_id);
+        task::spawn_blocking(move || {
+            fs::create_dir_all(&portraits_dir).map_err(|e| e.to_string())?;
+            let temp_path = image_path.with_extension("tmp");
+            fs::write(&temp_path, &bytes).map_err(|e| e.to_string())?;
+            fs::rename(&temp_path, &image_path).map_err(|e| e.to_string())
+        }).await.map_err(|e| e.to_string())?
+    }
+}
+
+impl Default for FavoritesStorage {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -1 +1,3 @@
-pub mod favorites;
\ No newline at end of file
+pub mod favorites;
+pub mod store;
+pub mod transfer;
\ No newline at end of file
@@ -0,0 +1,681 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use crate::api::search_query::CharacterSearchQuery;
+use crate::models::character::Character;
+use crate::models::collection::CustomList;
+use crate::models::favorite::{self, FavoriteRecord};
+use crate::models::saved_search::SavedSearch;
+use crate::storage::transfer::ImportMode;
+
+// Version written by this build; older files are migrated when they are opened
+pub const SCHEMA_VERSION: u32 = 6;
+// Recent searches kept in the history, newest first
+pub const MAX_SEARCH_HISTORY: usize = 20;
+
+// Stores opened in this process, one per file, so every FavoritesStorage shares the same index
+static OPEN_STORES: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<FavoritesStore>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// On-disk layout of the favorites file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoritesDocument {
+    pub version: u32,
+    pub favorites: Vec<FavoriteRecord>,
+    pub lists: Vec<CustomList>,
+    pub next_list_id: u32,
+    // Free-form tags per saved character, keyed by MAL id
+    pub tags: BTreeMap<u32, Vec<String>>,
+    // Recent Explore search texts, newest first
+    pub search_history: Vec<String>,
+    pub saved_searches: Vec<SavedSearch>,
+}
+
+impl Default for FavoritesDocument {
+    fn default() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            favorites: Vec::new(),
+            lists: Vec::new(),
+            next_list_id: 1,
+            tags: BTreeMap::new(),
+            search_history: Vec::new(),
+            saved_searches: Vec::new(),
+        }
+    }
+}
+
+// Everything removing a character took out, so the removal can be undone
+#[derive(Debug, Clone)]
+pub struct RemovedFavorite {
+    pub record: FavoriteRecord,
+    pub tags: Vec<String>,
+    // Lists the character belonged to
+    pub list_ids: Vec<u32>,
+}
+
+// The favorites document plus a `mal_id` index for constant-time lookups
+#[derive(Debug, Clone, Default)]
+pub struct FavoritesIndex {
+    document: FavoritesDocument,
+    positions: HashMap<u32, usize>,
+}
+
+impl FavoritesIndex {
+    fn new(document: FavoritesDocument) -> Self {
+        let mut index = Self {
+            document,
+            positions: HashMap::new(),
+        };
+        index.rebuild_positions();
+        index
+    }
+
+    pub fn document(&self) -> &FavoritesDocument {
+        &self.document
+    }
+
+    pub fn favorites(&self) -> &[FavoriteRecord] {
+        &self.document.favorites
+    }
+
+    pub fn get(&self, mal_id: u32) -> Option<&FavoriteRecord> {
+        self.positions
+            .get(&mal_id)
+            .map(|&position| &self.document.favorites[position])
+    }
+
+    pub fn contains(&self, mal_id: u32) -> bool {
+        self.positions.contains_key(&mal_id)
+    }
+
+    // Appends the character unless it is already stored; returns whether it was added
+    pub fn insert(&mut self, character: Character) -> bool {
+        if self.contains(character.mal_id) {
+            return false;
+        }
+        self.positions.insert(character.mal_id, self.document.favorites.len());
+        self.document.favorites.push(FavoriteRecord::new(character));
+        true
+    }
+
+    // Replaces a stored character's API data, keeping the user's own fields; returns whether it was present
+    pub fn update(&mut self, character: Character) -> bool {
+        match self.record_mut(character.mal_id) {
+            Ok(record) => {
+                record.character = character;
+                record.touch();
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn set_rating(&mut self, mal_id: u32, rating: Option<u8>) -> Result<(), String> {
+        let rating = favorite::validate_rating(rating)?;
+        let record = self.record_mut(mal_id)?;
+        record.rating = rating;
+        record.touch();
+        Ok(())
+    }
+
+    pub fn set_notes(&mut self, mal_id: u32, notes: &str) -> Result<(), String> {
+        let record = self.record_mut(mal_id)?;
+        record.notes = notes.to_string();
+        record.touch();
+        Ok(())
+    }
+
+    pub fn set_thumbnail(&mut self, mal_id: u32, thumbnail_url: Option<String>) -> Result<(), String> {
+        let record = self.record_mut(mal_id)?;
+        record.thumbnail_url = thumbnail_url;
+        record.touch();
+        Ok(())
+    }
+
+    // Removes the character along with its list memberships and tags
+    pub fn remove(&mut self, mal_id: u32) -> Option<RemovedFavorite> {
+        let position = self.positions.remove(&mal_id)?;
+        let list_ids = self.lists_containing(mal_id);
+        let record = self.document.favorites.remove(position);
+        self.rebuild_positions();
+
+        for list in self.document.lists.iter_mut() {
+            list.members.retain(|&member| member != mal_id);
+        }
+        let tags = self.document.tags.remove(&mal_id).unwrap_or_default();
+
+        Some(RemovedFavorite { record, tags, list_ids })
+    }
+
+    // Puts a removed character back with its tags and list memberships; lists deleted in the
+    // meantime are skipped. Returns false if the character was stored again already.
+    pub fn restore(&mut self, removed: RemovedFavorite) -> bool {
+        let mal_id = removed.record.mal_id();
+        if self.contains(mal_id) {
+            return false;
+        }
+        self.positions.insert(mal_id, self.document.favorites.len());
+        self.document.favorites.push(removed.record);
+
+        if !removed.tags.is_empty() {
+            self.document.tags.insert(mal_id, removed.tags);
+        }
+        for list in self.document.lists.iter_mut() {
+            if removed.list_ids.contains(&list.id) && !list.members.contains(&mal_id) {
+                list.members.push(mal_id);
+            }
+        }
+        true
+    }
+
+    // Brings in an imported collection. Characters already stored are skipped, tags are
+    // combined and lists are matched by name. Returns the ids of the characters added.
+    // Searches belong to this install, so they are neither imported nor replaced.
+    pub fn import(&mut self, imported: FavoritesDocument, mode: ImportMode) -> Vec<u32> {
+        if mode == ImportMode::Replace {
+            let mut document = FavoritesDocument::default();
+            document.search_history = std::mem::take(&mut self.document.search_history);
+            document.saved_searches = std::mem::take(&mut self.document.saved_searches);
+            *self = Self::new(document);
+        }
+
+        let mut added = Vec::new();
+        for record in imported.favorites {
+            let mal_id = record.mal_id();
+            if !self.contains(mal_id) {
+                self.positions.insert(mal_id, self.document.favorites.len());
+                self.document.favorites.push(record);
+                added.push(mal_id);
+            }
+        }
+
+        for (mal_id, tags) in imported.tags {
+            if self.contains(mal_id) {
+                let combined: Vec<String> = self.tags_for(mal_id).iter().cloned().chain(tags).collect();
+                let _ = self.set_tags(mal_id, &combined);
+            }
+        }
+
+        for list in imported.lists {
+            let existing = self
+                .document
+                .lists
+                .iter()
+                .find(|existing| existing.name.eq_ignore_ascii_case(list.name.trim()))
+                .map(|existing| existing.id);
+            let Some(list_id) = existing.or_else(|| self.create_list(&list.name).ok()) else {
+                continue;
+            };
+            for mal_id in list.members {
+                let _ = self.set_list_membership(list_id, mal_id, true);
+            }
+        }
+
+        added
+    }
+
+    pub fn lists(&self) -> &[CustomList] {
+        &self.document.lists
+    }
+
+    pub fn list(&self, list_id: u32) -> Option<&CustomList> {
+        self.document.lists.iter().find(|list| list.id == list_id)
+    }
+
+    pub fn create_list(&mut self, name: &str) -> Result<u32, String> {
+        let name = self.validate_list_name(name, None)?;
+        let id = self.document.next_list_id;
+        self.document.next_list_id += 1;
+        self.document.lists.push(CustomList {
+            id,
+            name,
+            members: Vec::new(),
+        });
+        Ok(id)
+    }
+
+    pub fn rename_list(&mut self, list_id: u32, name: &str) -> Result<(), String> {
+        let name = self.validate_list_name(name, Some(list_id))?;
+        self.list_mut(list_id)?.name = name;
+        Ok(())
+    }
+
+    pub fn delete_list(&mut self, list_id: u32) -> Result<(), String> {
+        let position = self.list_position(list_id)?;
+        self.document.lists.remove(position);
+        Ok(())
+    }
+
+    // Moves a list to `position` in the sidebar order, clamped to the end
+    pub fn move_list(&mut self, list_id: u32, position: usize) -> Result<(), String> {
+        let current = self.list_position(list_id)?;
+        let list = self.document.lists.remove(current);
+        let position = position.min(self.document.lists.len());
+        self.document.lists.insert(position, list);
+        Ok(())
+    }
+
+    pub fn set_list_membership(&mut self, list_id: u32, mal_id: u32, member: bool) -> Result<(), String> {
+        if member && !self.contains(mal_id) {
+            return Err("Only saved characters can be added to a list".to_string());
+        }
+
+        let list = self.list_mut(list_id)?;
+        let present = list.members.contains(&mal_id);
+        if member && !present {
+            list.members.push(mal_id);
+        } else if !member && present {
+            list.members.retain(|&existing| existing != mal_id);
+        }
+        Ok(())
+    }
+
+    pub fn lists_containing(&self, mal_id: u32) -> Vec<u32> {
+        self.document
+            .lists
+            .iter()
+            .filter(|list| list.members.contains(&mal_id))
+            .map(|list| list.id)
+            .collect()
+    }
+
+    pub fn tags_for(&self, mal_id: u32) -> &[String] {
+        self.document.tags.get(&mal_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Replaces a character's tags, trimming them and dropping blanks and duplicates
+    pub fn set_tags(&mut self, mal_id: u32, tags: &[String]) -> Result<(), String> {
+        if !self.contains(mal_id) {
+            return Err("Only saved characters can be tagged".to_string());
+        }
+
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+            if !normalized.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+
+        if normalized.is_empty() {
+            self.document.tags.remove(&mal_id);
+        } else {
+            self.document.tags.insert(mal_id, normalized);
+        }
+        Ok(())
+    }
+
+    // Every tag in use, sorted case-insensitively
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.document.tags.values().flatten() {
+            if !tags.iter().any(|existing| existing.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags
+    }
+
+    pub fn search_history(&self) -> &[String] {
+        &self.document.search_history
+    }
+
+    // Moves `text` to the front of the history, dropping the oldest entries past the limit
+    pub fn record_search(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+
+        let history = &mut self.document.search_history;
+        history.retain(|existing| !existing.eq_ignore_ascii_case(text));
+        history.insert(0, text.to_string());
+        history.truncate(MAX_SEARCH_HISTORY);
+    }
+
+    // Returns whether the entry was in the history
+    pub fn remove_search(&mut self, text: &str) -> bool {
+        let history = &mut self.document.search_history;
+        let before = history.len();
+        history.retain(|existing| existing != text);
+        history.len() != before
+    }
+
+    pub fn saved_searches(&self) -> &[SavedSearch] {
+        &self.document.saved_searches
+    }
+
+    pub fn save_search(&mut self, name: &str, query: CharacterSearchQuery) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Saved search name cannot be empty".to_string());
+        }
+
+        let saved = &mut self.document.saved_searches;
+        if let Some(existing) = saved.iter().find(|saved| saved.query == query) {
+            return Err(format!("This search is already saved as \"{}\"", existing.name));
+        }
+        if saved.iter().any(|saved| saved.name.eq_ignore_ascii_case(name)) {
+            return Err(format!("A saved search named \"{}\" already exists", name));
+        }
+
+        saved.push(SavedSearch {
+            name: name.to_string(),
+            query,
+        });
+        Ok(())
+    }
+
+    pub fn remove_saved_search(&mut self, name: &str) -> Result<(), String> {
+        let saved = &mut self.document.saved_searches;
+        let position = saved
+            .iter()
+            .position(|saved| saved.name == name)
+            .ok_or_else(|| format!("No saved search named \"{}\"", name))?;
+        saved.remove(position);
+        Ok(())
+    }
+
+    fn record_mut(&mut self, mal_id: u32) -> Result<&mut FavoriteRecord, String> {
+        let position = *self
+            .positions
+            .get(&mal_id)
+            .ok_or_else(|| format!("Character {} is not saved", mal_id))?;
+        Ok(&mut self.document.favorites[position])
+    }
+
+    fn list_position(&self, list_id: u32) -> Result<usize, String> {
+        self.document
+            .lists
+            .iter()
+            .position(|list| list.id == list_id)
+            .ok_or_else(|| format!("List {} does not exist", list_id))
+    }
+
+    fn list_mut(&mut self, list_id: u32) -> Result<&mut CustomList, String> {
+        let position = self.list_position(list_id)?;
+        Ok(&mut self.document.lists[position])
+    }
+
+    fn validate_list_name(&self, name: &str, renaming: Option<u32>) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("List name cannot be empty".to_string());
+        }
+
+        let taken = self
+            .document
+            .lists
+            .iter()
+            .any(|list| Some(list.id) != renaming && list.name.eq_ignore_ascii_case(name));
+        if taken {
+            return Err(format!("A list named \"{}\" already exists", name));
+        }
+
+        Ok(name.to_string())
+    }
+
+    fn rebuild_positions(&mut self) {
+        self.positions = self
+            .document
+            .favorites
+            .iter()
+            .enumerate()
+            .map(|(position, record)| (record.mal_id(), position))
+            .collect();
+    }
+}
+
+// A favorites file kept in memory and written back with atomic replace-on-commit transactions
+pub struct FavoritesStore {
+    path: PathBuf,
+    index: FavoritesIndex,
+    // Modification time of the file when it was last read or written by us
+    loaded_mtime: Option<SystemTime>,
+    loaded: bool,
+}
+
+impl FavoritesStore {
+    fn open(path: &Path) -> Arc<Mutex<FavoritesStore>> {
+        let mut stores = lock(&OPEN_STORES);
+        stores
+            .entry(path.to_path_buf())
+            .or_insert_with(|| {
+                Arc::new(Mutex::new(FavoritesStore {
+                    path: path.to_path_buf(),
+                    index: FavoritesIndex::default(),
+                    loaded_mtime: None,
+                    loaded: false,
+                }))
+            })
+            .clone()
+    }
+
+    // Runs `f` against the current contents of the store at `path`
+    pub fn read<R>(path: &Path, f: impl FnOnce(&FavoritesIndex) -> R) -> Result<R, String> {
+        let store = Self::open(path);
+        let mut store = lock(&store);
+        store.refresh_if_changed()?;
+        Ok(f(&store.index))
+    }
+
+    // Runs `f` against a copy of the store and commits it only if `f` succeeds and the
+    // file is written. Other processes are kept out by an exclusive lock file meanwhile.
+    pub fn write<R>(
+        path: &Path,
+        f: impl FnOnce(&mut FavoritesIndex) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let store = Self::open(path);
+        let mut store = lock(&store);
+        let _file_lock = FileLock::acquire(&store.lock_path())?;
+
+        // Pick up changes another process committed since we last looked
+        store.refresh_if_changed()?;
+
+        let mut index = store.index.clone();
+        let result = f(&mut index)?;
+        store.save(&index.document)?;
+        store.index = index;
+        Ok(result)
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.path.with_extension("lock")
+    }
+
+    fn refresh_if_changed(&mut self) -> Result<(), String> {
+        let mtime = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if self.loaded && mtime == self.loaded_mtime {
+            return Ok(());
+        }
+
+        let document = self.load()?;
+        self.index = FavoritesIndex::new(document);
+        self.loaded_mtime = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        self.loaded = true;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<FavoritesDocument, String> {
+        if !self.path.exists() {
+            return Ok(FavoritesDocument::default());
+        }
+
+        let contents = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        if contents.trim().is_empty() {
+            return Ok(FavoritesDocument::default());
+        }
+
+        let value: Value = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        let (document, migrated_from) = migrate(value)?;
+
+        if let Some(old_version) = migrated_from {
+            // Keep the pre-migration file around in case something goes wrong
+            let backup_path = self.path.with_extension(format!("v{}.bak", old_version));
+            if !backup_path.exists() {
+                fs::copy(&self.path, &backup_path).map_err(|e| e.to_string())?;
+            }
+            self.write_atomically(&document)?;
+        }
+
+        Ok(document)
+    }
+
+    fn save(&mut self, document: &FavoritesDocument) -> Result<(), String> {
+        self.write_atomically(document)?;
+        self.loaded_mtime = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        Ok(())
+    }
+
+    // Writes to a temporary file, syncs it and renames it over the real file, so a crash
+    // leaves either the old or the new collection but never a truncated one
+    fn write_atomically(&self, document: &FavoritesDocument) -> Result<(), String> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        let json = serde_json::to_string_pretty(document).map_err(|e| e.to_string())?;
+        let temp_path = self.path.with_extension("json.tmp");
+
+        let mut file = File::create(&temp_path).map_err(|e| e.to_string())?;
+        file.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+        drop(file);
+
+        fs::rename(&temp_path, &self.path).map_err(|e| e.to_string())
+    }
+}
+
+// Brings a favorites file of any known version up to `SCHEMA_VERSION`.
+// Returns the document and, if it had to be migrated, the version it started at.
+pub(crate) fn migrate(mut value: Value) -> Result<(FavoritesDocument, Option<u32>), String> {
+    let original_version = match &value {
+        // Version 1 was a bare array of characters
+        Value::Array(_) => 1,
+        Value::Object(object) => object
+            .get("version")
+            .and_then(Value::as_u64)
+            .map(|version| version as u32)
+            .ok_or("Favorites file has no schema version")?,
+        _ => return Err("Favorites file is not a JSON array or object".to_string()),
+    };
+
+    if original_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Favorites file uses schema version {}, but this build only understands up to {}",
+            original_version, SCHEMA_VERSION
+        ));
+    }
+
+    let mut version = original_version;
+    while version < SCHEMA_VERSION {
+        value = match version {
+            1 => migrate_v1_to_v2(value),
+            2 => migrate_v2_to_v3(value),
+            3 => migrate_v3_to_v4(value),
+            4 => migrate_v4_to_v5(value),
+            5 => migrate_v5_to_v6(value),
+            _ => return Err(format!("No migration from favorites schema version {}", version)),
+        };
+        version += 1;
+    }
+
+    let document: FavoritesDocument = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    let migrated_from = (original_version < SCHEMA_VERSION).then_some(original_version);
+    Ok((document, migrated_from))
+}
+
+fn migrate_v1_to_v2(value: Value) -> Value {
+    serde_json::json!({
+        "version": 2,
+        "favorites": value,
+    })
+}
+
+// Version 3 added custom lists and tags
+fn migrate_v2_to_v3(mut value: Value) -> Value {
+    if let Value::Object(object) = &mut value {
+        object.insert("version".to_string(), Value::from(3));
+        object.entry("lists").or_insert_with(|| Value::Array(Vec::new()));
+        object.entry("next_list_id").or_insert_with(|| Value::from(1));
+        object.entry("tags").or_insert_with(|| Value::Object(Default::default()));
+    }
+    value
+}
+
+// Version 4 wrapped each character in a record carrying rating, notes and timestamps.
+// The real date added is unknown, so every migrated favorite gets the migration time and
+// keeps its position in the file, which is the order it was added in.
+fn migrate_v3_to_v4(mut value: Value) -> Value {
+    let now = favorite::unix_now();
+    if let Value::Object(object) = &mut value {
+        object.insert("version".to_string(), Value::from(4));
+        if let Some(Value::Array(favorites)) = object.get_mut("favorites") {
+            for entry in favorites.iter_mut() {
+                let character = entry.take();
+                *entry = serde_json::json!({
+                    "character": character,
+                    "rating": null,
+                    "notes": "",
+                    "added_at": now,
+                    "updated_at": now,
+                });
+            }
+        }
+    }
+    value
+}
+
+// Version 5 added the Explore search history and saved searches
+fn migrate_v4_to_v5(mut value: Value) -> Value {
+    if let Value::Object(object) = &mut value {
+        object.insert("version".to_string(), Value::from(5));
+        object.entry("search_history").or_insert_with(|| Value::Array(Vec::new()));
+        object.entry("saved_searches").or_insert_with(|| Value::Array(Vec::new()));
+    }
+    value
+}
+
+// Version 6 let each record carry the gallery picture chosen as its thumbnail
+fn migrate_v5_to_v6(mut value: Value) -> Value {
+    if let Value::Object(object) = &mut value {
+        object.insert("version".to_string(), Value::from(6));
+        if let Some(Value::Array(favorites)) = object.get_mut("favorites") {
+            for record in favorites.iter_mut() {
+                if let Value::Object(record) = record {
+                    record.entry("thumbnail_url").or_insert(Value::Null);
+                }
+            }
+        }
+    }
+    value
+}
+
+// Exclusive advisory lock on a sidecar file, released when dropped
+struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    fn acquire(path: &Path) -> Result<Self, String> {
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| e.to_string())?;
+        file.lock().map_err(|e| e.to_string())?;
+        Ok(Self { _file: file })
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -0,0 +1,268 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::models::favorite::{self, FavoriteRecord};
+use crate::storage::store::{self, FavoritesDocument};
+
+const CSV_COLUMNS: [&str; 9] = [
+    "mal_id", "name", "name_kanji", "favorites", "rating", "added_at", "updated_at", "url", "notes",
+];
+
+// File formats the collection can be exported to and imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferFormat {
+    // The favorites file itself, with lists and tags
+    Json,
+    // One row per character with its personal data, for spreadsheets
+    Csv,
+    // One MAL id per line
+    MalIds,
+}
+
+impl TransferFormat {
+    pub const ALL: [TransferFormat; 3] = [TransferFormat::Json, TransferFormat::Csv, TransferFormat::MalIds];
+
+    // Picks the format from the file extension, falling back to native JSON
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("csv") => TransferFormat::Csv,
+            Some("txt") => TransferFormat::MalIds,
+            _ => TransferFormat::Json,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            TransferFormat::Json => "json",
+            TransferFormat::Csv => "csv",
+            TransferFormat::MalIds => "txt",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TransferFormat::Json => "Waifu Viewer collection (JSON)",
+            TransferFormat::Csv => "Spreadsheet (CSV)",
+            TransferFormat::MalIds => "MyAnimeList IDs (plain text)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    // Keep the current collection and add characters it does not have yet
+    Merge,
+    // Discard the current collection, lists and tags
+    Replace,
+}
+
+// An imported character known only by id, plus whatever personal data came with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdOnlyFavorite {
+    pub mal_id: u32,
+    pub rating: Option<u8>,
+    pub notes: String,
+    pub added_at: Option<u64>,
+}
+
+impl IdOnlyFavorite {
+    fn new(mal_id: u32) -> Self {
+        Self {
+            mal_id,
+            rating: None,
+            notes: String::new(),
+            added_at: None,
+        }
+    }
+}
+
+// A parsed import file. Entries in `id_only` still need their character data fetched.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedCollection {
+    pub document: FavoritesDocument,
+    pub id_only: Vec<IdOnlyFavorite>,
+}
+
+pub fn export(document: &FavoritesDocument, format: TransferFormat) -> Result<String, String> {
+    match format {
+        TransferFormat::Json => {
+            // The search history and saved searches stay with this install
+            let mut document = document.clone();
+            document.search_history.clear();
+            document.saved_searches.clear();
+            serde_json::to_string_pretty(&document).map_err(|e| e.to_string())
+        }
+        TransferFormat::Csv => Ok(export_csv(&document.favorites)),
+        TransferFormat::MalIds => Ok(document
+            .favorites
+            .iter()
+            .map(|record| format!("{}\n", record.mal_id()))
+            .collect()),
+    }
+}
+
+pub fn parse(contents: &str, format: TransferFormat) -> Result<ImportedCollection, String> {
+    let mut collection = match format {
+        TransferFormat::Json => {
+            let value: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+            let (document, _) = store::migrate(value)?;
+            ImportedCollection {
+                document,
+                id_only: Vec::new(),
+            }
+        }
+        TransferFormat::Csv => ImportedCollection {
+            document: FavoritesDocument::default(),
+            id_only: parse_csv(contents)?,
+        },
+        TransferFormat::MalIds => ImportedCollection {
+            document: FavoritesDocument::default(),
+            id_only: parse_mal_ids(contents)?,
+        },
+    };
+
+    dedupe(&mut collection);
+    Ok(collection)
+}
+
+// Keeps the first occurrence of every `mal_id` across both halves of the import
+fn dedupe(collection: &mut ImportedCollection) {
+    let mut seen = HashSet::new();
+    collection.document.favorites.retain(|record| seen.insert(record.mal_id()));
+    collection.id_only.retain(|entry| seen.insert(entry.mal_id));
+}
+
+fn export_csv(records: &[FavoriteRecord]) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push('\n');
+
+    for record in records {
+        let character = &record.character;
+        let fields = [
+            character.mal_id.to_string(),
+            character.name.clone(),
+            character.name_kanji.clone().unwrap_or_default(),
+            character.favorites.to_string(),
+            record.rating.map(|rating| rating.to_string()).unwrap_or_default(),
+            record.added_at.to_string(),
+            record.updated_at.to_string(),
+            character.url.clone(),
+            record.notes.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| escape_csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Reads rows with a header naming at least a `mal_id` column; other known columns are optional
+fn parse_csv(contents: &str) -> Result<Vec<IdOnlyFavorite>, String> {
+    let mut rows = split_csv_rows(contents)?.into_iter();
+    let header = rows.next().ok_or("The CSV file is empty")?;
+    let column = |name: &str| header.iter().position(|field| field.trim().eq_ignore_ascii_case(name));
+
+    let id_column = column("mal_id").ok_or("The CSV file has no mal_id column")?;
+    let rating_column = column("rating");
+    let notes_column = column("notes");
+    let added_column = column("added_at");
+
+    let mut entries = Vec::new();
+    for (line, row) in rows.enumerate() {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+
+        let field = |column: Option<usize>| column.and_then(|column| row.get(column)).map(|field| field.trim());
+        let mal_id = field(Some(id_column))
+            .and_then(|id| id.parse::<u32>().ok())
+            .ok_or_else(|| format!("Row {} has no valid mal_id", line + 2))?;
+
+        let mut entry = IdOnlyFavorite::new(mal_id);
+        entry.rating = field(rating_column)
+            .and_then(|rating| rating.parse::<u8>().ok())
+            .and_then(|rating| favorite::validate_rating(Some(rating)).ok().flatten());
+        entry.notes = field(notes_column).unwrap_or_default().to_string();
+        entry.added_at = field(added_column).and_then(|added_at| added_at.parse::<u64>().ok());
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+// Splits RFC 4180 style CSV into rows of fields, honouring quoted commas, quotes and newlines
+fn split_csv_rows(contents: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = contents.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("The CSV file ends inside a quoted field".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+// One id per line; blank lines and `#` comments are skipped and MAL character URLs are accepted
+fn parse_mal_ids(contents: &str) -> Result<Vec<IdOnlyFavorite>, String> {
+    let mut entries = Vec::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let id = match line.split_once("/character/") {
+            Some((_, rest)) => rest.split('/').next().unwrap_or_default(),
+            None => line,
+        };
+        let mal_id = id
+            .parse::<u32>()
+            .map_err(|_| format!("Line {} is not a MyAnimeList character id: {}", line_number + 1, line))?;
+        entries.push(IdOnlyFavorite::new(mal_id));
+    }
+
+    Ok(entries)
+}
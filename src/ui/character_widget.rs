@@ -1,26 +1,32 @@
-use libadwaita::gtk::{self, gdk_pixbuf, glib, prelude::*, Box, Image, Label, Orientation, Button};
+use libadwaita::gtk::{self, glib, prelude::*, Box, Image, Label, Orientation, Button};
+use log::error;
 
 use crate::models::character::Character;
 use crate::storage::favorites::FavoritesStorage;
-use async_channel;
-use std::io::Cursor;
+use crate::ui::favorites_model::FavoritesModel;
+use crate::ui::utils::app_settings::AppSettings;
+use crate::ui::utils::image_loader::{ImageLoadError, ImageLoader, ImageSize};
+use crate::ui::utils::toast;
 
 pub struct CharacterWidget {
     pub widget: Box,
+    pub button_box: Box,
 }
 
 impl CharacterWidget {
     pub fn new(character: Character) -> Self {
-        Self::new_with_options(character, false, || {})
+        Self::build(character, false)
     }
 
-    pub fn new_with_delete_callback<F>(character: Character, delete_callback: F) -> Self 
-    where F: Fn() + 'static + Clone {
-        Self::new_with_options(character, true, delete_callback)
+    // A card for a character from a cast or voice listing, which carries only its name and
+    // picture; favoriting it saves the full character instead
+    pub fn new_partial(character: Character) -> Self {
+        Self::build(character, true)
     }
 
-    fn new_with_options<F>(character: Character, show_delete: bool, delete_callback: F) -> Self 
-    where F: Fn() + 'static + Clone {
+    fn build(character: Character, partial: bool) -> Self {
+        let (image_width, image_height) = AppSettings::shared().card_size().image_size();
+
         // Create the main container with fixed size
         let widget = Box::builder()
             .orientation(Orientation::Vertical)
@@ -29,8 +35,8 @@ impl CharacterWidget {
             .margin_bottom(15)
             .margin_start(15)
             .margin_end(15)
-            .width_request(200)
-            .height_request(350)
+            .width_request(image_width + 20)
+            .height_request(image_height + 80)
             .build();
 
         // Create image widget with placeholder
@@ -42,79 +48,23 @@ impl CharacterWidget {
             .build();
         
         // Set a fixed size for the image area
-        image.set_size_request(180, 270);
+        image.set_size_request(image_width, image_height);
         
+        // Prefer the portrait stored with a favorite so cards render offline
+        let image_url = FavoritesStorage::new()
+            .local_image_uri(character.mal_id)
+            .or_else(|| character.images.jpg.image_url.clone());
+
         // Handle image loading if URL is available
-        if let Some(image_url) = &character.images.jpg.image_url {
-            let image_url = image_url.clone();
-            let (sender, receiver) = async_channel::unbounded::<Vec<u8>>();
-            
-            // Spawn background thread for image loading
-            std::thread::spawn(move || {
-                match reqwest::blocking::get(&image_url) {
-                    Ok(response) => {
-                        if let Ok(bytes) = response.bytes() {
-                            // Send bytes to main thread
-                            let _ = sender.send_blocking(bytes.to_vec());
-                        }
-                    }
-                    Err(_) => {
-                        // Send empty vector to indicate error
-                        let _ = sender.send_blocking(Vec::new());
-                    }
-                }
-            });
-            
-            // Handle image loading on main thread
-            let image_clone = image.clone();
-            glib::MainContext::default().spawn_local(async move {
-                match receiver.recv().await {
-                    Ok(bytes) => {
-                        if !bytes.is_empty() {
-                            // Create pixbuf from bytes using Cursor
-                            let cursor = Cursor::new(bytes);
-                            match gdk_pixbuf::Pixbuf::from_read(cursor) {
-                                Ok(pixbuf) => {
-                                    // Scale the pixbuf to fit within our constraints
-                                    let current_width = pixbuf.width();
-                                    let current_height = pixbuf.height();
-                                    
-                                    // Calculate new dimensions maintaining aspect ratio
-                                    let max_width = 180;
-                                    let max_height = 270;
-                                    
-                                    let scale_factor = f64::min(
-                                        max_width as f64 / current_width as f64,
-                                        max_height as f64 / current_height as f64
-                                    );
-                                    
-                                    let new_width = (current_width as f64 * scale_factor) as i32;
-                                    let new_height = (current_height as f64 * scale_factor) as i32;
-                                    
-                                    if let Some(scaled_pixbuf) = pixbuf.scale_simple(
-                                        new_width.max(1), 
-                                        new_height.max(1), 
-                                        gdk_pixbuf::InterpType::Bilinear
-                                    ) {
-                                        image_clone.set_from_pixbuf(Some(&scaled_pixbuf));
-                                    } else {
-                                        image_clone.set_from_pixbuf(Some(&pixbuf));
-                                    }
-                                }
-                                Err(_) => {
-                                    // Use Adwaita's image-missing icon for load errors
-                                    image_clone.set_icon_name(Some("image-missing"));
-                                }
-                            }
-                        } else {
-                            // Error occurred during image loading, use network-offline icon
-                            image_clone.set_icon_name(Some("network-offline-symbolic"));
-                        }
-                    }
-                    Err(_) => {
-                        // Channel error, use image-missing icon
-                        image_clone.set_icon_name(Some("image-missing"));
-                    }
+        if let Some(image_url) = &image_url {
+            let size = ImageSize::Fit { width: image_width, height: image_height };
+            ImageLoader::shared().load_for(&image, image_url, size, |image, result| {
+                match result {
+                    Ok(texture) => image.set_paintable(Some(&texture)),
+                    // Error occurred during image loading, use network-offline icon
+                    Err(ImageLoadError::Network) => image.set_icon_name(Some("network-offline-symbolic")),
+                    // Use Adwaita's image-missing icon for load errors
+                    Err(ImageLoadError::Decode) => image.set_icon_name(Some("image-missing")),
                 }
             });
         } else {
@@ -139,7 +89,7 @@ impl CharacterWidget {
             .ellipsize(gtk::pango::EllipsizeMode::End)
             .halign(gtk::Align::Center)
             .valign(gtk::Align::Center)
-            .width_request(180)
+            .width_request(image_width)
             .height_request(40)
             .build();
 
@@ -154,54 +104,138 @@ impl CharacterWidget {
             .halign(gtk::Align::Center)
             .build();
 
-        // Create favorite button
-        let favorite_button = Button::builder()
-            .icon_name("add-symbolic")
-            .tooltip_text("Add to favorites")
-            .build();
+        // Create favorite button, reflecting whether the character is already saved
+        let model = FavoritesModel::shared();
+        let favorite_button = Button::new();
+        Self::update_favorite_button(&favorite_button, model.is_favorite(character.mal_id));
 
         // Handle favorite button click
         let character_clone = character.clone();
-        favorite_button.connect_clicked(move |_| {
-            let storage = FavoritesStorage::new();
-            let character_clone = character_clone.clone();
-            glib::MainContext::default().spawn_local(async move {
-                if let Err(e) = storage.add_favorite(character_clone).await {
-                    eprintln!("Failed to add favorite: {}", e);
-                }
-            });
+        favorite_button.connect_clicked(move |button| {
+            Self::toggle_favorite(button, character_clone.clone(), partial);
+        });
+
+        // Keep the button in sync when the character is (un)favorited from another card or tab
+        let mal_id = character.mal_id;
+        let weak_button = favorite_button.downgrade();
+        let subscription = model.subscribe(move |change| {
+            if let Some(button) = weak_button.upgrade().filter(|_| change.affects(mal_id)) {
+                Self::update_favorite_button(&button, FavoritesModel::shared().is_favorite(mal_id));
+            }
+        });
+        widget.connect_destroy(move |_| {
+            FavoritesModel::shared().unsubscribe(subscription);
         });
 
         button_box.append(&favorite_button);
 
-        // Add delete button only if requested (for favorites page)
-        if show_delete {
-            let delete_button = Button::builder()
-                .icon_name("user-trash-symbolic")
-                .tooltip_text("Remove from favorites")
-                .build();
-
-            let character_clone = character.clone();
-            let delete_callback_clone = delete_callback.clone();
-            delete_button.connect_clicked(move |_| {
-                let storage = FavoritesStorage::new();
-                let character_clone = character_clone.clone();
-                let delete_callback_clone = delete_callback_clone.clone();
-                glib::MainContext::default().spawn_local(async move {
-                    if let Err(e) = storage.remove_favorite(character_clone).await {
-                        eprintln!("Failed to remove favorite: {}", e);
-                    } else {
-                        // Call the callback to refresh the favorites page
-                        delete_callback_clone();
-                    }
-                });
-            });
+        widget.append(&button_box);
+
+        // Clicking the card (outside its buttons) opens the character detail page
+        widget.set_cursor_from_name(Some("pointer"));
+        let click_gesture = gtk::GestureClick::new();
+        click_gesture.connect_released(move |gesture, _, _, _| {
+            let _ = gesture
+                .widget()
+                .activate_action("win.show-character", Some(&mal_id.to_variant()));
+        });
+        widget.add_controller(click_gesture);
+
+        Self { widget, button_box }
+    }
+
+    // Adds an icon button next to the favorite toggle, e.g. "roll again" on random cards
+    pub fn add_action<F>(&self, icon_name: &str, tooltip: &str, on_click: F) -> Button
+    where
+        F: Fn(&Button) + 'static,
+    {
+        let button = Button::builder()
+            .icon_name(icon_name)
+            .tooltip_text(tooltip)
+            .build();
+        button.connect_clicked(on_click);
+        self.button_box.append(&button);
+        button
+    }
 
-            button_box.append(&delete_button);
+    // Adds a dimmed line under the name, e.g. the character's role in a series
+    pub fn add_caption(&self, text: &str) {
+        let caption = Label::builder()
+            .label(text)
+            .halign(gtk::Align::Center)
+            .css_classes(vec!["dim-label".to_string(), "caption".to_string()])
+            .build();
+        self.widget.insert_child_after(&caption, self.button_box.prev_sibling().as_ref());
+    }
+
+    fn update_favorite_button(button: &Button, is_favorite: bool) {
+        if is_favorite {
+            button.set_icon_name("starred-symbolic");
+            button.set_tooltip_text(Some("Remove from favorites"));
+            button.add_css_class("accent");
+        } else {
+            button.set_icon_name("add-symbolic");
+            button.set_tooltip_text(Some("Add to favorites"));
+            button.remove_css_class("accent");
         }
+    }
 
-        widget.append(&button_box);
+    fn toggle_favorite(button: &Button, character: Character, partial: bool) {
+        // Look the overlay up now: on the favorites page the card goes away once removed
+        let overlay = toast::overlay_for(button);
+        let model = FavoritesModel::shared();
+        let button = button.clone();
+        button.set_sensitive(false);
+
+        glib::MainContext::default().spawn_local(async move {
+            let toast = if model.is_favorite(character.mal_id) {
+                match model.remove(character.clone()).await {
+                    Ok(removed) => {
+                        let toast = toast::plain_toast(&format!("Removed {} from favorites", character.name));
+                        toast.set_button_label(Some("Undo"));
+                        toast.connect_button_clicked(move |_| {
+                            let character = character.clone();
+                            let removed = removed.clone();
+                            glib::MainContext::default().spawn_local(async move {
+                                let model = FavoritesModel::shared();
+                                let result = match removed {
+                                    Some(removed) => model.restore(removed).await,
+                                    None => Self::add(&model, character, partial).await,
+                                };
+                                if let Err(e) = result {
+                                    error!("Failed to restore favorite: {}", e);
+                                }
+                            });
+                        });
+                        toast
+                    }
+                    Err(e) => {
+                        error!("Failed to remove favorite: {}", e);
+                        toast::plain_toast(&format!("Could not remove {}: {}", character.name, e))
+                    }
+                }
+            } else {
+                match Self::add(&model, character.clone(), partial).await {
+                    Ok(()) => toast::plain_toast(&format!("Added {} to favorites", character.name)),
+                    Err(e) => {
+                        error!("Failed to add favorite: {}", e);
+                        toast::plain_toast(&format!("Could not add {}: {}", character.name, e))
+                    }
+                }
+            };
 
-        Self { widget }
+            button.set_sensitive(true);
+            if let Some(overlay) = overlay {
+                overlay.add_toast(toast);
+            }
+        });
+    }
+
+    async fn add(model: &FavoritesModel, character: Character, partial: bool) -> Result<(), String> {
+        if partial {
+            model.add_by_id(character.mal_id).await
+        } else {
+            model.add(character).await
+        }
     }
 }
\ No newline at end of file
@@ -1,11 +1,20 @@
 use libadwaita as adw;
 use adw::prelude::*;
 use libadwaita::gtk;
+use gtk::{gio, glib};
 
 use crate::ui::headerbar::WaifuHeaderBar;
 use crate::ui::content::WaifuContent;
+use crate::ui::debug_log_window::DebugLogWindow;
 use crate::ui::dialogs::DialogManager;
 use crate::ui::handlers::SearchHandler;
+use crate::ui::pages::character_detail_page::CharacterDetailPage;
+use crate::ui::pages::person_page::PersonPage;
+use crate::ui::pages::series_detail_page::SeriesDetailPage;
+use crate::ui::preferences_window::PreferencesWindow;
+use crate::ui::series_card::SeriesKind;
+use crate::ui::series_handler::SeriesHandler;
+use crate::ui::transfer_handler::TransferHandler;
 
 pub struct SignalConnector;
 
@@ -14,6 +23,7 @@ impl SignalConnector {
         window: &adw::ApplicationWindow,
         header_bar: &WaifuHeaderBar,
         content: &WaifuContent,
+        navigation_view: &adw::NavigationView,
     ) {
         // Connect about functionality
         let window_clone = window.clone();
@@ -21,7 +31,65 @@ impl SignalConnector {
             DialogManager::show_about_dialog(&window_clone);
         });
 
+        // Connect import and export of the favorites collection
+        let window_clone = window.clone();
+        header_bar.connect_import(move || {
+            TransferHandler::import(&window_clone);
+        });
+
+        let window_clone = window.clone();
+        header_bar.connect_export(move || {
+            TransferHandler::export(&window_clone);
+        });
+
+        let window_clone = window.clone();
+        header_bar.connect_preferences(move || {
+            PreferencesWindow::present(&window_clone);
+        });
+
+        let window_clone = window.clone();
+        header_bar.connect_debug_log(move || {
+            DebugLogWindow::present(&window_clone);
+        });
+
         // Connect search and fetch functionality
         SearchHandler::connect_search_signals(&content.explore_page);
+        SeriesHandler::connect(&content.explore_page.series_browser);
+
+        // Character cards activate "win.show-character" with their MAL id to open the detail page
+        let show_character = gio::SimpleAction::new("show-character", Some(glib::VariantTy::UINT32));
+        let navigation_view_clone = navigation_view.clone();
+        show_character.connect_activate(move |_, parameter| {
+            if let Some(mal_id) = parameter.and_then(|parameter| parameter.get::<u32>()) {
+                let detail_page = CharacterDetailPage::new(mal_id);
+                navigation_view_clone.push(&detail_page.page);
+            }
+        });
+        window.add_action(&show_character);
+
+        // Series cards and appearances activate "win.show-anime" or "win.show-manga" the same way
+        for kind in SeriesKind::ALL {
+            let action_name = kind.action_name().trim_start_matches("win.");
+            let show_series = gio::SimpleAction::new(action_name, Some(glib::VariantTy::UINT32));
+            let navigation_view = navigation_view.clone();
+            show_series.connect_activate(move |_, parameter| {
+                if let Some(mal_id) = parameter.and_then(|parameter| parameter.get::<u32>()) {
+                    let detail_page = SeriesDetailPage::new(kind, mal_id);
+                    navigation_view.push(&detail_page.page);
+                }
+            });
+            window.add_action(&show_series);
+        }
+
+        // Voice actor rows activate "win.show-person" with the person's MAL id
+        let show_person = gio::SimpleAction::new("show-person", Some(glib::VariantTy::UINT32));
+        let navigation_view = navigation_view.clone();
+        show_person.connect_activate(move |_, parameter| {
+            if let Some(mal_id) = parameter.and_then(|parameter| parameter.get::<u32>()) {
+                let person_page = PersonPage::new(mal_id);
+                navigation_view.push(&person_page.page);
+            }
+        });
+        window.add_action(&show_person);
     }
 }
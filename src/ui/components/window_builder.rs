@@ -5,6 +5,7 @@ use gtk::{Box, Orientation};
 
 use crate::ui::headerbar::WaifuHeaderBar;
 use crate::ui::content::WaifuContent;
+use crate::ui::utils::app_settings::AppSettings;
 
 pub struct WindowBuilder;
 
@@ -13,6 +14,7 @@ impl WindowBuilder {
         app: &adw::Application,
         header_bar: &WaifuHeaderBar,
         content: &WaifuContent,
+        navigation_view: &adw::NavigationView,
     ) -> adw::ApplicationWindow {
         let main_box = Box::builder()
             .orientation(Orientation::Vertical)
@@ -22,12 +24,25 @@ impl WindowBuilder {
         main_box.append(header_bar.container());
         main_box.append(content.container());
 
+        let main_page = adw::NavigationPage::builder()
+            .title("Waifu Viewer")
+            .tag("main")
+            .child(&main_box)
+            .build();
+        navigation_view.add(&main_page);
+
+        // Toasts raised anywhere in the window (e.g. by character cards) show up here
+        let toast_overlay = adw::ToastOverlay::new();
+        toast_overlay.set_child(Some(navigation_view));
+
+        let state = AppSettings::shared().window_state();
         adw::ApplicationWindow::builder()
             .application(app)
             .title("Waifu Viewer")
-            .default_width(1000)
-            .default_height(800)
-            .content(&main_box)
+            .default_width(state.width)
+            .default_height(state.height)
+            .maximized(state.maximized)
+            .content(&toast_overlay)
             .build()
     }
 }
\ No newline at end of file
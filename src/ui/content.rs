@@ -61,4 +61,25 @@ impl WaifuContent {
     pub fn container(&self) -> &Box {
         &self.container
     }
+
+    // Name of the visible tab, e.g. "explore"
+    pub fn visible_page(&self) -> String {
+        self.view_stack
+            .visible_child_name()
+            .map(|name| name.to_string())
+            .unwrap_or_default()
+    }
+
+    // Switches tabs; unknown names are ignored
+    pub fn set_visible_page(&self, name: &str) {
+        if self.view_stack.child_by_name(name).is_some() {
+            self.view_stack.set_visible_child_name(name);
+        }
+    }
+}
+
+impl Default for WaifuContent {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,169 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::{gio, glib, Box, Button, Label, Orientation, ScrolledWindow, Switch, TextBuffer, TextView};
+use log::{error, info, Level};
+
+use crate::utils::logger::{self, LogLine, LOG_ENV};
+use crate::ui::utils::app_settings::AppSettings;
+use crate::ui::utils::toast;
+
+// Lines the view keeps before dropping the oldest
+const MAX_VIEW_LINES: i32 = 5000;
+
+// Live view of the app's log, for attaching to bug reports
+pub struct DebugLogWindow;
+
+impl DebugLogWindow {
+    pub fn present(parent: &adw::ApplicationWindow) {
+        let buffer = TextBuffer::new(None);
+        buffer.create_tag(Some("warn"), &[("foreground", &"#e5a50a")]);
+        buffer.create_tag(Some("error"), &[("foreground", &"#e01b24")]);
+        for line in logger::recent_lines() {
+            Self::append_line(&buffer, &line);
+        }
+
+        let text_view = TextView::builder()
+            .buffer(&buffer)
+            .editable(false)
+            .cursor_visible(false)
+            .monospace(true)
+            .wrap_mode(gtk::WrapMode::WordChar)
+            .top_margin(8)
+            .bottom_margin(8)
+            .left_margin(8)
+            .right_margin(8)
+            .build();
+
+        let scrolled_window = ScrolledWindow::builder()
+            .child(&text_view)
+            .vexpand(true)
+            .hexpand(true)
+            .build();
+
+        let copy_button = Button::builder()
+            .icon_name("edit-copy-symbolic")
+            .tooltip_text("Copy Log")
+            .build();
+        let folder_button = Button::builder()
+            .icon_name("folder-open-symbolic")
+            .tooltip_text("Show Log File")
+            .sensitive(logger::log_file_path().is_some())
+            .build();
+
+        let verbose_switch = Switch::builder()
+            .valign(gtk::Align::Center)
+            .build();
+        let verbose_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .build();
+        verbose_box.append(&Label::new(Some("Verbose")));
+        verbose_box.append(&verbose_switch);
+        // Shares the key with the Preferences window
+        match AppSettings::shared().gsettings() {
+            Some(settings) if !logger::filter_from_env() => {
+                settings.bind("verbose-logging", &verbose_switch, "active").build();
+                verbose_box.set_tooltip_text(Some("Also log debug messages"));
+            }
+            Some(_) => {
+                verbose_box.set_sensitive(false);
+                verbose_box.set_tooltip_text(Some(&format!("Set by {}", LOG_ENV)));
+            }
+            None => verbose_box.set_sensitive(false),
+        }
+
+        let header_bar = adw::HeaderBar::new();
+        header_bar.pack_start(&copy_button);
+        header_bar.pack_start(&folder_button);
+        header_bar.pack_end(&verbose_box);
+
+        let toast_overlay = adw::ToastOverlay::new();
+        toast_overlay.set_child(Some(&scrolled_window));
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&header_bar);
+        toolbar_view.set_content(Some(&toast_overlay));
+
+        let window = adw::Window::builder()
+            .title("Debug Log")
+            .transient_for(parent)
+            .default_width(900)
+            .default_height(560)
+            .content(&toolbar_view)
+            .build();
+
+        verbose_switch.connect_active_notify(|switch| {
+            let verbose = switch.is_active();
+            info!("Verbose logging {}", if verbose { "enabled" } else { "disabled" });
+        });
+
+        copy_button.connect_clicked({
+            let buffer = buffer.clone();
+            move |button| {
+                let (start, end) = buffer.bounds();
+                button.clipboard().set_text(&buffer.text(&start, &end, false));
+                if let Some(overlay) = toast::overlay_for(button) {
+                    overlay.add_toast(toast::plain_toast("Log copied to the clipboard"));
+                }
+            }
+        });
+
+        folder_button.connect_clicked({
+            let window = window.clone();
+            move |_| {
+                let Some(path) = logger::log_file_path() else {
+                    return;
+                };
+                let launcher = gtk::FileLauncher::new(Some(&gio::File::for_path(path)));
+                launcher.open_containing_folder(Some(&window), None::<&gio::Cancellable>, |result| {
+                    if let Err(e) = result {
+                        error!("Failed to show the log file: {}", e);
+                    }
+                });
+            }
+        });
+
+        // Follow new lines while the window is open
+        let receiver = logger::subscribe();
+        let follow = glib::MainContext::default().spawn_local({
+            let text_view = text_view.downgrade();
+            let scrolled_window = scrolled_window.clone();
+            async move {
+                while let Ok(line) = receiver.recv().await {
+                    let Some(text_view) = text_view.upgrade() else {
+                        break;
+                    };
+                    let adjustment = scrolled_window.vadjustment();
+                    let at_bottom = adjustment.value() + adjustment.page_size() >= adjustment.upper() - 1.0;
+
+                    let buffer = text_view.buffer();
+                    Self::append_line(&buffer, &line);
+                    if buffer.line_count() > MAX_VIEW_LINES {
+                        let mut start = buffer.start_iter();
+                        let mut cut = buffer.iter_at_line(buffer.line_count() - MAX_VIEW_LINES).unwrap_or(start);
+                        buffer.delete(&mut start, &mut cut);
+                    }
+                    if at_bottom {
+                        text_view.scroll_to_mark(&buffer.get_insert(), 0.0, false, 0.0, 1.0);
+                    }
+                }
+            }
+        });
+        window.connect_destroy(move |_| follow.abort());
+
+        window.present();
+        text_view.scroll_to_mark(&buffer.get_insert(), 0.0, false, 0.0, 1.0);
+    }
+
+    fn append_line(buffer: &TextBuffer, line: &LogLine) {
+        let mut end = buffer.end_iter();
+        let text = format!("{}\n", line);
+        match line.level {
+            Level::Error => buffer.insert_with_tags_by_name(&mut end, &text, &["error"]),
+            Level::Warn => buffer.insert_with_tags_by_name(&mut end, &text, &["warn"]),
+            _ => buffer.insert(&mut end, &text),
+        }
+        buffer.place_cursor(&buffer.end_iter());
+    }
+}
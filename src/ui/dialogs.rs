@@ -1,10 +1,6 @@
 use libadwaita as adw;
 use adw::prelude::*;
 use libadwaita::gtk;
-use gtk::{ResponseType, FlowBox, Spinner};
-use gtk::glib;
-
-use crate::ui::handlers::SearchHandler;
 
 pub struct DialogManager;
 
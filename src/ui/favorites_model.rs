@@ -0,0 +1,218 @@
+use log::error;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crate::models::character::Character;
+use crate::storage::favorites::FavoritesStorage;
+use crate::storage::store::RemovedFavorite;
+use crate::ui::utils::api_handler::ApiHandler;
+use crate::ui::utils::image_loader::ImageLoader;
+
+thread_local! {
+    static SHARED_MODEL: FavoritesModel = FavoritesModel::new();
+}
+
+#[derive(Debug, Clone)]
+pub enum FavoritesChange {
+    Added(Character),
+    Removed(Character),
+    // The rating, notes or thumbnail of a saved character were edited
+    DetailsChanged(u32),
+    // Lists or tags were created, edited or reassigned
+    CollectionsChanged,
+    // The whole collection may have changed, e.g. after a re-sync or import
+    Reloaded,
+}
+
+impl FavoritesChange {
+    // Whether a widget showing `mal_id` needs to refresh for this change
+    pub fn affects(&self, mal_id: u32) -> bool {
+        match self {
+            FavoritesChange::Added(character) | FavoritesChange::Removed(character) => {
+                character.mal_id == mal_id
+            }
+            FavoritesChange::DetailsChanged(changed) => *changed == mal_id,
+            FavoritesChange::CollectionsChanged => false,
+            FavoritesChange::Reloaded => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionId(u64);
+
+type Listener = Rc<dyn Fn(&FavoritesChange)>;
+
+struct Inner {
+    storage: FavoritesStorage,
+    favorite_ids: RefCell<HashSet<u32>>,
+    listeners: RefCell<Vec<(SubscriptionId, Listener)>>,
+    next_subscription: Cell<u64>,
+}
+
+// Main-thread view of the favorites collection that notifies subscribers whenever it
+// changes, so every card and page reflects membership without re-reading storage
+#[derive(Clone)]
+pub struct FavoritesModel {
+    inner: Rc<Inner>,
+}
+
+impl FavoritesModel {
+    fn new() -> Self {
+        let model = Self {
+            inner: Rc::new(Inner {
+                storage: FavoritesStorage::new(),
+                favorite_ids: RefCell::new(HashSet::new()),
+                listeners: RefCell::new(Vec::new()),
+                next_subscription: Cell::new(0),
+            }),
+        };
+        model.load_ids();
+        model
+    }
+
+    // The model shared by every widget on the main thread
+    pub fn shared() -> Self {
+        SHARED_MODEL.with(|model| model.clone())
+    }
+
+    pub fn is_favorite(&self, mal_id: u32) -> bool {
+        self.inner.favorite_ids.borrow().contains(&mal_id)
+    }
+
+    pub async fn add(&self, character: Character) -> Result<(), String> {
+        self.inner.storage.add_favorite(character.clone()).await?;
+        self.inner.favorite_ids.borrow_mut().insert(character.mal_id);
+        self.notify(&FavoritesChange::Added(character));
+        Ok(())
+    }
+
+    // Saves a character known only from a cast or voice listing, which lacks most of its
+    // details, by fetching the full character first
+    pub async fn add_by_id(&self, mal_id: u32) -> Result<(), String> {
+        let character = ApiHandler::new().get_character(mal_id).await.map_err(|e| e.to_string())?;
+        self.add(character).await
+    }
+
+    // Returns what was removed, so `restore` can undo it
+    pub async fn remove(&self, character: Character) -> Result<Option<RemovedFavorite>, String> {
+        let removed = self.inner.storage.remove_favorite(character.clone()).await?;
+        self.inner.favorite_ids.borrow_mut().remove(&character.mal_id);
+        self.notify(&FavoritesChange::Removed(character));
+        Ok(removed)
+    }
+
+    // Brings back a removed character with its rating, notes, tags and lists
+    pub async fn restore(&self, removed: RemovedFavorite) -> Result<(), String> {
+        let character = removed.record.character.clone();
+        self.inner.storage.restore_favorite(removed).await?;
+        self.inner.favorite_ids.borrow_mut().insert(character.mal_id);
+        self.notify(&FavoritesChange::Added(character));
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(())
+    }
+
+    pub async fn set_rating(&self, mal_id: u32, rating: Option<u8>) -> Result<(), String> {
+        self.inner.storage.set_rating(mal_id, rating).await?;
+        self.notify(&FavoritesChange::DetailsChanged(mal_id));
+        Ok(())
+    }
+
+    pub async fn set_notes(&self, mal_id: u32, notes: &str) -> Result<(), String> {
+        self.inner.storage.set_notes(mal_id, notes).await?;
+        self.notify(&FavoritesChange::DetailsChanged(mal_id));
+        Ok(())
+    }
+
+    pub async fn set_thumbnail(&self, mal_id: u32, thumbnail_url: Option<String>) -> Result<(), String> {
+        self.inner.storage.set_thumbnail(mal_id, thumbnail_url).await?;
+        // The stored portrait keeps its path, so drop the texture decoded from the old file
+        if let Some(uri) = self.inner.storage.local_image_uri(mal_id) {
+            ImageLoader::shared().forget(&uri);
+        }
+        self.notify(&FavoritesChange::DetailsChanged(mal_id));
+        Ok(())
+    }
+
+    pub async fn create_list(&self, name: &str) -> Result<u32, String> {
+        let list_id = self.inner.storage.create_list(name).await?;
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(list_id)
+    }
+
+    pub async fn rename_list(&self, list_id: u32, name: &str) -> Result<(), String> {
+        self.inner.storage.rename_list(list_id, name).await?;
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(())
+    }
+
+    pub async fn delete_list(&self, list_id: u32) -> Result<(), String> {
+        self.inner.storage.delete_list(list_id).await?;
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(())
+    }
+
+    pub async fn move_list(&self, list_id: u32, position: usize) -> Result<(), String> {
+        self.inner.storage.move_list(list_id, position).await?;
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(())
+    }
+
+    pub async fn set_list_membership(&self, list_id: u32, mal_id: u32, member: bool) -> Result<(), String> {
+        self.inner.storage.set_list_membership(list_id, mal_id, member).await?;
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(())
+    }
+
+    pub async fn set_tags(&self, mal_id: u32, tags: Vec<String>) -> Result<(), String> {
+        self.inner.storage.set_tags(mal_id, tags).await?;
+        self.notify(&FavoritesChange::CollectionsChanged);
+        Ok(())
+    }
+
+    // Re-reads membership from storage after it was changed behind the model's back
+    pub fn reload(&self) {
+        self.load_ids();
+        self.notify(&FavoritesChange::Reloaded);
+    }
+
+    pub fn subscribe<F>(&self, listener: F) -> SubscriptionId
+    where
+        F: Fn(&FavoritesChange) + 'static,
+    {
+        let id = SubscriptionId(self.inner.next_subscription.get());
+        self.inner.next_subscription.set(id.0 + 1);
+        self.inner.listeners.borrow_mut().push((id, Rc::new(listener)));
+        id
+    }
+
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.inner.listeners.borrow_mut().retain(|(listener_id, _)| *listener_id != id);
+    }
+
+    fn load_ids(&self) {
+        match self.inner.storage.get_favorites() {
+            Ok(favorites) => {
+                *self.inner.favorite_ids.borrow_mut() =
+                    favorites.iter().map(|record| record.mal_id()).collect();
+            }
+            Err(e) => error!("Failed to load favorites: {}", e),
+        }
+    }
+
+    fn notify(&self, change: &FavoritesChange) {
+        // Listeners may subscribe or unsubscribe while handling the change
+        let listeners: Vec<Listener> = self
+            .inner
+            .listeners
+            .borrow()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+
+        for listener in listeners {
+            listener(change);
+        }
+    }
+}
@@ -4,88 +4,259 @@ use adw::prelude::*;
 use libadwaita::gtk;
 use gtk::{FlowBox, Spinner, Label, Button};
 use gtk::glib;
+use log::warn;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::time::Duration;
 
 use crate::ui::pages::explore_page::ExplorePage;
 use crate::ui::character_widget::CharacterWidget;
 use crate::ui::utils::api_handler::ApiHandler;
+use crate::ui::utils::app_settings::AppSettings;
 use crate::ui::utils::error_display;
+use crate::ui::utils::paged_listing::{self, Paging};
+use crate::ui::utils::toast;
+use crate::ui::search_history::SearchHistory;
+use crate::storage::favorites::FavoritesStorage;
 
+use crate::api::jikan::{JikanError, JikanResponse};
+use crate::api::search_query::CharacterSearchQuery;
 use crate::models::character::Character;
 
+// Characters rolled by the random button; each one is a separate rate-limited request
+const RANDOM_BATCH_SIZE: usize = 12;
+// Quiet period after the last keystroke before a search is sent
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(350);
+
+#[derive(Clone, PartialEq)]
+enum BrowseSource {
+    Top,
+    Search(CharacterSearchQuery),
+    // Rolled in one batch and never paged
+    Random,
+}
+
+// Tracks which listing the Explore grid is showing and how far it has been paged
+#[derive(Default)]
+struct BrowseState {
+    source: Option<BrowseSource>,
+    paging: Paging,
+    // Ids of the random cards on screen, so re-rolls do not repeat them
+    rolled: HashSet<u32>,
+    // Pending search-as-you-type timeout
+    debounce: Option<glib::SourceId>,
+    // Listing and offset from the last session, scrolled back to once enough pages are loaded
+    restore_scroll: Option<(BrowseSource, f64)>,
+}
+
+impl AsMut<Paging> for BrowseState {
+    fn as_mut(&mut self) -> &mut Paging {
+        &mut self.paging
+    }
+}
+
+type SharedBrowseState = Rc<RefCell<BrowseState>>;
+
+// How a failed listing is shown in place of its results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorNotice {
+    Offline,
+    RateLimited,
+    // Anything else, shown as the context followed by the error
+    Message(String),
+}
+
 pub struct SearchHandler;
 
 impl SearchHandler {
     pub fn connect_search_signals(explore_page: &ExplorePage) {
-        let character_container = explore_page.character_container.clone();
-        let loading_spinner = explore_page.loading_spinner.clone();
-        
+        let state: SharedBrowseState = Rc::new(RefCell::new(BrowseState::default()));
+
         // Connect search functionality
         let search_entry = explore_page.search_entry.clone();
         let search_button = explore_page.search_button.clone();
-        let container = character_container.clone();
-        let spinner = loading_spinner.clone();
-        
-        let search_callback = move |query: String| {
-            let container = container.clone();
-            let spinner = spinner.clone();
-            let ctx = glib::MainContext::default();
-            ctx.spawn_local(async move {
-                Self::prepare_loading_state(&container, &spinner);
-                Self::search_characters(container, spinner, &query).await;
-            });
+
+        let search_callback = {
+            let explore_page = explore_page.clone();
+            let state = state.clone();
+            move |query: CharacterSearchQuery| {
+                let explore_page = explore_page.clone();
+                paged_listing::spawn_listing(&state, {
+                    let state = state.clone();
+                    async move {
+                        Self::search_characters(explore_page, state, query).await;
+                    }
+                });
+            }
         };
 
-        search_button.connect_clicked({
-            let search_entry = search_entry.clone();
-            let search_callback = search_callback.clone();
-            move |_| {
-                let query = search_entry.text().to_string();
-                if !query.is_empty() {
+        // Searches need a name or a starting letter; Jikan would otherwise list everyone
+        let submit_search = {
+            let explore_page = explore_page.clone();
+            let state = state.clone();
+            move || {
+                Self::cancel_debounce(&state);
+                let query = explore_page.search_query();
+                if query.has_criteria() {
                     search_callback(query);
                 }
             }
+        };
+
+        // Explicit searches, as opposed to debounced ones, go into the history
+        let history = SearchHistory::connect(explore_page, submit_search.clone());
+
+        search_button.connect_clicked({
+            let history = history.clone();
+            move |_| history.submit()
         });
 
-        search_entry.connect_activate({
-            let search_callback = search_callback.clone();
-            move |entry| {
-                let query = entry.text().to_string();
-                if !query.is_empty() {
-                    search_callback(query);
-                }
+        search_entry.connect_activate(move |_| history.submit());
+
+        // Search as the user types, once they pause, unless the text did not change the query
+        search_entry.connect_changed({
+            let explore_page = explore_page.clone();
+            let state = state.clone();
+            let submit_search = submit_search.clone();
+            move |_| {
+                Self::cancel_debounce(&state);
+                let source = glib::timeout_add_local_once(SEARCH_DEBOUNCE, {
+                    let explore_page = explore_page.clone();
+                    let state = state.clone();
+                    let submit_search = submit_search.clone();
+                    move || {
+                        // The source is gone once it fires, so it must not be removed again
+                        state.borrow_mut().debounce = None;
+                        let query = explore_page.search_query();
+                        if state.borrow().source != Some(BrowseSource::Search(query)) {
+                            submit_search();
+                        }
+                    }
+                });
+                state.borrow_mut().debounce = Some(source);
             }
         });
 
+        // Changing a filter re-runs the search right away
+        for dropdown in [
+            &explore_page.order_by_dropdown,
+            &explore_page.sort_dropdown,
+            &explore_page.letter_dropdown,
+        ] {
+            let submit_search = submit_search.clone();
+            dropdown.connect_selected_notify(move |_| submit_search());
+        }
+        explore_page.limit_spin.connect_value_changed({
+            let submit_search = submit_search.clone();
+            move |_| submit_search()
+        });
+
 
         // Connect fetch button functionality
         Self::connect_fetch_button(
             explore_page.fetch_button.clone(),
-            character_container,
-            loading_spinner,
+            explore_page.clone(),
+            state.clone(),
         );
+
+        Self::connect_infinite_scroll(explore_page, state.clone());
+        Self::restore_session(explore_page, &state, submit_search);
+    }
+
+    // Re-runs the last session's search and arranges to scroll back to where it was left
+    fn restore_session(explore_page: &ExplorePage, state: &SharedBrowseState, submit_search: impl Fn()) {
+        let settings = AppSettings::shared();
+        let Some(query) = settings.last_search() else {
+            return;
+        };
+
+        explore_page.apply_search_query(&query);
+        // The query as the page describes it is the one the search will run with
+        let source = BrowseSource::Search(explore_page.search_query());
+        let scroll = settings.window_state().scroll;
+        if scroll > 0.0 {
+            state.borrow_mut().restore_scroll = Some((source, scroll));
+        }
+        submit_search();
     }
 
     fn connect_fetch_button(
         fetch_button: Button,
-        character_container: FlowBox,
-        loading_spinner: Spinner,
+        explore_page: ExplorePage,
+        state: SharedBrowseState,
     ) {
-        fetch_button.connect_clicked(move |_| {
-            let ctx = glib::MainContext::default();
-            let container = character_container.clone();
-            let spinner = loading_spinner.clone();
-            ctx.spawn_local(async move {
-                Self::prepare_loading_state(&container, &spinner);
-                Self::fetch_and_display_top_characters(container, spinner).await;
+        fetch_button.connect_clicked({
+            let explore_page = explore_page.clone();
+            let state = state.clone();
+            move |_| {
+                let explore_page = explore_page.clone();
+                paged_listing::spawn_listing(&state, {
+                    let state = state.clone();
+                    async move {
+                        Self::fetch_random_characters(explore_page, state).await;
+                    }
+                });
+            }
+        });
+
+        explore_page.top_button.clone().connect_clicked(move |_| {
+            let explore_page = explore_page.clone();
+            paged_listing::spawn_listing(&state, {
+                let state = state.clone();
+                async move {
+                    Self::fetch_and_display_top_characters(explore_page, state).await;
+                }
             });
         });
     }
 
+    fn cancel_debounce(state: &SharedBrowseState) {
+        let pending = state.borrow_mut().debounce.take();
+        if let Some(pending) = pending {
+            pending.remove();
+        }
+    }
+
+    fn connect_infinite_scroll(explore_page: &ExplorePage, state: SharedBrowseState) {
+        let on_scroll = {
+            let explore_page = explore_page.clone();
+            move |adjustment: &gtk::Adjustment| {
+                Self::continue_restore(&state, adjustment);
+                if paged_listing::near_bottom(adjustment) {
+                    Self::load_next_page(explore_page.clone(), state.clone());
+                }
+            }
+        };
+        paged_listing::connect_scroll(&explore_page.characters_window, on_scroll);
+    }
+
+    // Scrolls toward the restored offset as far as the loaded pages allow; being at the
+    // bottom then loads the next page, until the offset is reachable or there are no more
+    fn continue_restore(state: &SharedBrowseState, adjustment: &gtk::Adjustment) {
+        let target = {
+            let mut state = state.borrow_mut();
+            let Some((source, offset)) = state.restore_scroll.clone() else {
+                return;
+            };
+            if state.paging.loading || state.source.as_ref() != Some(&source) {
+                return;
+            }
+
+            let reachable = (adjustment.upper() - adjustment.page_size()).max(0.0);
+            if offset <= reachable || !state.paging.has_next_page {
+                state.restore_scroll = None;
+            }
+            offset.min(reachable)
+        };
+        adjustment.set_value(target);
+    }
+
     fn prepare_loading_state(container: &FlowBox, spinner: &Spinner) {
         spinner.set_visible(true);
         spinner.start();
         container.set_visible(false);
-        
+
         // Clear existing children
         while let Some(child) = container.first_child() {
             container.remove(&child);
@@ -98,87 +269,326 @@ impl SearchHandler {
         container.set_visible(true);
     }
 
-    pub async fn fetch_and_display_top_characters(container: FlowBox, loading_spinner: Spinner) {
+    // Resets paging for a new listing and returns its generation
+    fn start_browsing(explore_page: &ExplorePage, state: &SharedBrowseState, source: BrowseSource) -> u32 {
+        // Only searches are re-run on the next launch
+        let search = match &source {
+            BrowseSource::Search(query) => Some(query),
+            _ => None,
+        };
+        AppSettings::shared().set_last_search(search);
+
+        let generation = {
+            let mut state = state.borrow_mut();
+            if state.restore_scroll.as_ref().is_some_and(|(pending, _)| *pending != source) {
+                state.restore_scroll = None;
+            }
+            state.source = Some(source);
+            state.rolled.clear();
+            state.paging.restart()
+        };
+
+        explore_page.load_more_spinner.set_visible(false);
+        explore_page.load_more_spinner.stop();
+        Self::prepare_loading_state(&explore_page.character_container, &explore_page.loading_spinner);
+
+        generation
+    }
+
+    fn is_current(state: &SharedBrowseState, generation: u32) -> bool {
+        state.borrow().paging.is_current(generation)
+    }
+
+    fn record_page(state: &SharedBrowseState, page: u32, response: &JikanResponse) {
+        let has_next_page = response
+            .pagination
+            .as_ref()
+            .is_some_and(|pagination| pagination.has_next_page);
+        state.borrow_mut().paging.record_page(page, has_next_page);
+    }
+
+    async fn fetch_page(source: &BrowseSource, page: u32) -> Result<JikanResponse, JikanError> {
+        let api_handler = ApiHandler::new();
+
+        match source {
+            BrowseSource::Top => api_handler.get_top_characters(page).await,
+            BrowseSource::Search(query) => api_handler.search_characters(&query.clone().page(page)).await,
+            // Never reached: random listings report no next page
+            BrowseSource::Random => Ok(JikanResponse { pagination: None, data: Vec::new() }),
+        }
+    }
+
+    async fn fetch_and_display_top_characters(explore_page: ExplorePage, state: SharedBrowseState) {
+        let generation = Self::start_browsing(&explore_page, &state, BrowseSource::Top);
+        let container = &explore_page.character_container;
+
         let loading_label = Label::builder()
             .label("Loading top waifus...")
             .build();
         container.insert(&loading_label, -1);
 
-        let api_handler = ApiHandler::new();
+        let result = Self::fetch_page(&BrowseSource::Top, 1).await;
+        if !Self::is_current(&state, generation) {
+            return;
+        }
 
-        match api_handler.get_top_characters().await {
-            Ok(characters) => {
+        match result {
+            Ok(response) => {
                 container.remove(&loading_label);
-                Self::add_character_widgets(&container, &characters, 20).await;
+                Self::record_page(&state, 1, &response);
+                Self::add_character_widgets(container, &response.data).await;
             }
             Err(e) => {
                 container.remove(&loading_label);
-                Self::handle_error(&container, &e, "Error fetching characters");
+                Self::handle_error(container, &e, "Error fetching characters");
             }
         }
-        
-        Self::finish_loading_state(&container, &loading_spinner);
+
+        Self::finish_loading_state(container, &explore_page.loading_spinner);
+        state.borrow_mut().paging.loading = false;
     }
-    
-    async fn search_characters(container: FlowBox, loading_spinner: Spinner, query: &str) {
+
+    async fn fetch_random_characters(explore_page: ExplorePage, state: SharedBrowseState) {
+        let generation = Self::start_browsing(&explore_page, &state, BrowseSource::Random);
+        let container = &explore_page.character_container;
+
         let loading_label = Label::builder()
-            .label(&format!("Searching for \"{}\"...", query))
+            .label("Rolling random waifus...")
             .build();
         container.insert(&loading_label, -1);
 
-        let api_handler = ApiHandler::new();
+        let exclude = Self::random_exclusions(&explore_page, &state);
+        let result = ApiHandler::new().get_random_characters(RANDOM_BATCH_SIZE, &exclude).await;
+        if !Self::is_current(&state, generation) {
+            return;
+        }
 
-        match api_handler.search_characters(query).await {
+        container.remove(&loading_label);
+        match result {
             Ok(characters) => {
+                state.borrow_mut().rolled = characters.iter().map(|character| character.mal_id).collect();
+                for character in characters {
+                    Self::add_random_card(&explore_page, &state, generation, character, -1);
+                }
+            }
+            Err(e) => Self::handle_error(container, &e, "Error rolling random characters"),
+        }
+
+        Self::finish_loading_state(container, &explore_page.loading_spinner);
+        state.borrow_mut().paging.loading = false;
+    }
+
+    // Ids a roll must not return: the cards already shown and, if asked, saved favorites
+    fn random_exclusions(explore_page: &ExplorePage, state: &SharedBrowseState) -> HashSet<u32> {
+        let mut exclude = state.borrow().rolled.clone();
+        if explore_page.exclude_favorites_check.is_active() {
+            match FavoritesStorage::new().get_favorites() {
+                Ok(favorites) => exclude.extend(favorites.iter().map(|record| record.mal_id())),
+                Err(e) => warn!("Failed to load favorites to exclude: {}", e),
+            }
+        }
+        exclude
+    }
+
+    fn add_random_card(
+        explore_page: &ExplorePage,
+        state: &SharedBrowseState,
+        generation: u32,
+        character: Character,
+        position: i32,
+    ) {
+        let mal_id = character.mal_id;
+        let card = CharacterWidget::new(character);
+        let weak_widget = card.widget.downgrade();
+        card.add_action("media-playlist-shuffle-symbolic", "Roll again", {
+            let explore_page = explore_page.clone();
+            let state = state.clone();
+            move |button| {
+                if let Some(widget) = weak_widget.upgrade() {
+                    Self::reroll_card(explore_page.clone(), state.clone(), generation, &widget, mal_id, button);
+                }
+            }
+        });
+        explore_page.character_container.insert(&card.widget, position);
+    }
+
+    // Replaces one random card with a fresh roll in the same spot
+    fn reroll_card(
+        explore_page: ExplorePage,
+        state: SharedBrowseState,
+        generation: u32,
+        card: &gtk::Box,
+        mal_id: u32,
+        button: &Button,
+    ) {
+        button.set_sensitive(false);
+        let exclude = Self::random_exclusions(&explore_page, &state);
+        let weak_card = card.downgrade();
+        let weak_button = button.downgrade();
+
+        glib::MainContext::default().spawn_local(async move {
+            let result = ApiHandler::new().get_random_characters(1, &exclude).await;
+            if !Self::is_current(&state, generation) {
+                return;
+            }
+            let Some(child) = weak_card
+                .upgrade()
+                .and_then(|card| card.parent())
+                .and_downcast::<gtk::FlowBoxChild>()
+            else {
+                return;
+            };
+
+            let message = match result.map(|characters| characters.into_iter().next()) {
+                Ok(Some(character)) => {
+                    {
+                        let mut state = state.borrow_mut();
+                        state.rolled.remove(&mal_id);
+                        state.rolled.insert(character.mal_id);
+                    }
+                    let position = child.index();
+                    explore_page.character_container.remove(&child);
+                    Self::add_random_card(&explore_page, &state, generation, character, position);
+                    return;
+                }
+                Ok(None) => "No new character turned up, try again".to_string(),
+                Err(e) => {
+                    warn!("Failed to roll again: {}", e);
+                    format!("Could not roll again: {}", e)
+                }
+            };
+
+            if let Some(button) = weak_button.upgrade() {
+                button.set_sensitive(true);
+            }
+            if let Some(overlay) = toast::overlay_for(&explore_page.container) {
+                overlay.add_toast(toast::plain_toast(&message));
+            }
+        });
+    }
+
+    async fn search_characters(explore_page: ExplorePage, state: SharedBrowseState, query: CharacterSearchQuery) {
+        let description = Self::describe_search(&query);
+        let source = BrowseSource::Search(query);
+        let generation = Self::start_browsing(&explore_page, &state, source.clone());
+        let container = &explore_page.character_container;
+
+        let loading_label = Label::builder()
+            .label(format!("Searching for {}...", description))
+            .build();
+        container.insert(&loading_label, -1);
+
+        let result = Self::fetch_page(&source, 1).await;
+        if !Self::is_current(&state, generation) {
+            return;
+        }
+
+        match result {
+            Ok(response) => {
                 container.remove(&loading_label);
-                
-                if characters.is_empty() {
+                Self::record_page(&state, 1, &response);
+
+                if response.data.is_empty() {
                     let no_results_label = Label::builder()
-                        .label(&format!("No characters found for \"{}\"", query))
+                        .label(format!("No characters found for {}", description))
                         .build();
                     container.insert(&no_results_label, -1);
                 } else {
-                    Self::add_character_widgets(&container, &characters, 20).await;
+                    Self::add_character_widgets(container, &response.data).await;
                 }
             }
             Err(e) => {
                 container.remove(&loading_label);
-                Self::handle_error(&container, &e, "Error searching characters");
+                Self::handle_error(container, &e, "Error searching characters");
             }
         }
-        
-        Self::finish_loading_state(&container, &loading_spinner);
+
+        Self::finish_loading_state(container, &explore_page.loading_spinner);
+        state.borrow_mut().paging.loading = false;
     }
 
-    
+    fn describe_search(query: &CharacterSearchQuery) -> String {
+        match (query.text(), query.starting_letter()) {
+            (Some(text), Some(letter)) => format!("\"{}\" starting with {}", text, letter),
+            (Some(text), None) => format!("\"{}\"", text),
+            (None, Some(letter)) => format!("names starting with {}", letter),
+            (None, None) => "all characters".to_string(),
+        }
+    }
+
+    fn load_next_page(explore_page: ExplorePage, state: SharedBrowseState) {
+        let (source, page, generation) = {
+            let mut state = state.borrow_mut();
+            let Some(source) = state.source.clone() else {
+                return;
+            };
+            let Some((page, generation)) = state.paging.begin_next_page() else {
+                return;
+            };
+            (source, page, generation)
+        };
+
+        let spinner = explore_page.load_more_spinner.clone();
+        spinner.set_visible(true);
+        spinner.start();
+
+        paged_listing::spawn_listing(&state, {
+            let state = state.clone();
+            async move {
+                let result = Self::fetch_page(&source, page).await;
+                if !Self::is_current(&state, generation) {
+                    return;
+                }
+
+                spinner.set_visible(false);
+                spinner.stop();
+
+                let container = &explore_page.character_container;
+                match result {
+                    Ok(response) => {
+                        Self::record_page(&state, page, &response);
+                        Self::add_character_widgets(container, &response.data).await;
+                    }
+                    Err(e) => {
+                        // Stop paging so a persistent failure does not retry on every scroll
+                        state.borrow_mut().paging.has_next_page = false;
+                        Self::handle_error(container, &e, "Error loading more characters");
+                    }
+                }
+
+                state.borrow_mut().paging.loading = false;
+            }
+        });
+    }
 
     async fn add_character_widgets(
         container: &FlowBox,
         characters: &[Character],
-        limit: usize,
     ) {
-        for character in characters.iter().take(limit) {
+        for character in characters {
             let character_widget = CharacterWidget::new(character.clone());
             container.insert(&character_widget.widget, -1);
         }
     }
 
-    fn handle_error(container: &FlowBox, error: &crate::api::jikan::JikanError, context: &str) {
+    pub fn classify_error(error: &JikanError, context: &str) -> ErrorNotice {
         match error {
-            crate::api::jikan::JikanError::Network(req_err) if req_err.is_connect() || req_err.is_timeout() => {
-                let error_box = error_display::create_error_display(
-                    "network-offline-symbolic",
-                    "No Internet Connection",
-                    "Please check your internet connection and try again."
-                );
-                container.insert(&error_box, -1);
-            }
-            _ => {
+            JikanError::Network(req_err) if req_err.is_connect() || req_err.is_timeout() => ErrorNotice::Offline,
+            JikanError::Transport(_) => ErrorNotice::Offline,
+            JikanError::RateLimited => ErrorNotice::RateLimited,
+            _ => ErrorNotice::Message(format!("{}: {}", context, error)),
+        }
+    }
+
+    pub(crate) fn handle_error(container: &FlowBox, error: &JikanError, context: &str) {
+        match Self::classify_error(error, context) {
+            ErrorNotice::Message(message) => {
                 let error_label = Label::builder()
-                    .label(&format!("{}: {}", context, error))
+                    .label(message)
                     .build();
                 container.insert(&error_label, -1);
             }
+            _ => container.insert(&error_display::for_jikan_error(error, context), -1),
         }
     }
-}
\ No newline at end of file
+}
@@ -8,7 +8,10 @@ use gtk::{Box, Orientation, MenuButton, Popover, Button};
 pub struct WaifuHeaderBar {
     container: adw::HeaderBar,
     about_button: Button,
-    
+    import_button: Button,
+    export_button: Button,
+    preferences_button: Button,
+    debug_log_button: Button,
 }
 
 impl WaifuHeaderBar {
@@ -20,6 +23,18 @@ impl WaifuHeaderBar {
 
         // Create the popover menu
         let popover = Popover::new();
+        let import_button = Button::builder()
+            .label("Import Favorites…")
+            .build();
+        let export_button = Button::builder()
+            .label("Export Favorites…")
+            .build();
+        let preferences_button = Button::builder()
+            .label("Preferences")
+            .build();
+        let debug_log_button = Button::builder()
+            .label("Debug Log")
+            .build();
         let about_button = Button::builder()
             .label("About")
             .build();
@@ -32,6 +47,10 @@ impl WaifuHeaderBar {
             .margin_end(5)
             .build();
         
+        popover_box.append(&import_button);
+        popover_box.append(&export_button);
+        popover_box.append(&preferences_button);
+        popover_box.append(&debug_log_button);
         popover_box.append(&about_button);
         popover.set_child(Some(&popover_box));
         menu_button.set_popover(Some(&popover));
@@ -45,6 +64,10 @@ impl WaifuHeaderBar {
         Self {
             container,
             about_button,
+            import_button,
+            export_button,
+            preferences_button,
+            debug_log_button,
         }
     }
 
@@ -59,5 +82,37 @@ impl WaifuHeaderBar {
         });
     }
 
-    
+    pub fn connect_import<F>(&self, import_callback: F)
+    where F: Fn() + 'static {
+        self.import_button.connect_clicked(move |_| {
+            import_callback();
+        });
+    }
+
+    pub fn connect_export<F>(&self, export_callback: F)
+    where F: Fn() + 'static {
+        self.export_button.connect_clicked(move |_| {
+            export_callback();
+        });
+    }
+
+    pub fn connect_preferences<F>(&self, preferences_callback: F)
+    where F: Fn() + 'static {
+        self.preferences_button.connect_clicked(move |_| {
+            preferences_callback();
+        });
+    }
+
+    pub fn connect_debug_log<F>(&self, debug_log_callback: F)
+    where F: Fn() + 'static {
+        self.debug_log_button.connect_clicked(move |_| {
+            debug_log_callback();
+        });
+    }
+}
+
+impl Default for WaifuHeaderBar {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
@@ -2,8 +2,16 @@ pub mod window;
 pub mod headerbar;
 pub mod content;
 pub mod character_widget;
+pub mod picture_gallery;
+pub mod series_card;
+pub mod favorites_model;
 pub mod dialogs;
+pub mod preferences_window;
+pub mod debug_log_window;
 pub mod handlers;
+pub mod transfer_handler;
+pub mod search_history;
+pub mod series_handler;
 pub mod components;
 pub mod utils;
 pub mod pages;
@@ -0,0 +1,492 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::{glib, Align, Box, Button, Label, LinkButton, Orientation, Picture, ScrolledWindow, Spinner};
+use log::{error, warn};
+
+use crate::models::character::{CharacterFull, CharacterVoice, MediaEntry};
+use crate::models::favorite::{self, FavoriteRecord, MAX_RATING, MIN_RATING};
+use crate::storage::favorites::FavoritesStorage;
+use crate::ui::favorites_model::{FavoritesChange, FavoritesModel};
+use crate::ui::picture_gallery::PictureGallery;
+use crate::ui::series_card::SeriesKind;
+use crate::ui::utils::api_handler::ApiHandler;
+use crate::ui::utils::error_display;
+use crate::ui::utils::image_loader::{ImageLoader, ImageSize};
+use crate::ui::utils::labels;
+use crate::ui::utils::markdown;
+use crate::ui::utils::toast;
+
+pub struct CharacterDetailPage {
+    pub page: adw::NavigationPage,
+}
+
+impl CharacterDetailPage {
+    pub fn new(mal_id: u32) -> Self {
+        let loading_spinner = Spinner::builder()
+            .spinning(true)
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .width_request(48)
+            .height_request(48)
+            .vexpand(true)
+            .build();
+
+        let body = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(24)
+            .margin_top(20)
+            .margin_bottom(20)
+            .margin_start(20)
+            .margin_end(20)
+            .build();
+        body.append(&loading_spinner);
+
+        let clamp = adw::Clamp::builder()
+            .maximum_size(900)
+            .child(&body)
+            .build();
+
+        let scrolled_window = ScrolledWindow::builder()
+            .vexpand(true)
+            .hexpand(true)
+            .child(&clamp)
+            .build();
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        toolbar_view.set_content(Some(&scrolled_window));
+
+        let page = adw::NavigationPage::builder()
+            .title("Character")
+            .child(&toolbar_view)
+            .build();
+
+        let page_clone = page.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let api_handler = ApiHandler::new();
+            let result = api_handler.get_character_full(mal_id).await;
+            body.remove(&loading_spinner);
+
+            match result {
+                Ok(character) => {
+                    page_clone.set_title(&character.character.name);
+                    Self::populate(&body, &character);
+                }
+                Err(e) => {
+                    warn!("Failed to load character {}: {}", mal_id, e);
+                    body.append(&error_display::for_jikan_error(&e, "Could Not Load Character"));
+                }
+            }
+        });
+
+        Self { page }
+    }
+
+    fn populate(body: &Box, full: &CharacterFull) {
+        let character = &full.character;
+
+        let header_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(24)
+            .build();
+
+        let portrait = Picture::builder()
+            .can_shrink(true)
+            .width_request(225)
+            .height_request(350)
+            .valign(Align::Start)
+            .build();
+        if let Some(image_url) = &character.images.jpg.image_url {
+            ImageLoader::shared().load_for(&portrait, image_url, ImageSize::Original, |portrait, result| {
+                if let Ok(texture) = result {
+                    portrait.set_paintable(Some(&texture));
+                }
+            });
+        }
+        header_box.append(&portrait);
+
+        let info_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .valign(Align::Center)
+            .hexpand(true)
+            .build();
+
+        info_box.append(&labels::text_label(&character.name, &["title-1"]));
+
+        if let Some(name_kanji) = character.name_kanji.as_deref().filter(|name| !name.is_empty()) {
+            info_box.append(&labels::text_label(name_kanji, &["title-3", "dim-label"]));
+        }
+
+        if !character.nicknames.is_empty() {
+            let nicknames = format!("Also known as: {}", character.nicknames.join(", "));
+            info_box.append(&labels::text_label(&nicknames, &["body"]));
+        }
+
+        let favorites = format!("Favorited by {} members", character.favorites);
+        info_box.append(&labels::text_label(&favorites, &["caption", "dim-label"]));
+
+        let mal_link = LinkButton::builder()
+            .uri(&character.url)
+            .label("View on MyAnimeList")
+            .halign(Align::Start)
+            .build();
+        info_box.append(&mal_link);
+
+        header_box.append(&info_box);
+        body.append(&header_box);
+
+        if let Some(about) = character.about.as_deref().filter(|about| !about.trim().is_empty()) {
+            let about_box = Box::builder()
+                .orientation(Orientation::Vertical)
+                .spacing(8)
+                .build();
+            about_box.append(&labels::text_label("About", &["heading"]));
+
+            let about_label = labels::text_label(about.trim(), &["body"]);
+            about_label.set_selectable(true);
+            about_box.append(&about_label);
+
+            body.append(&about_box);
+        }
+
+        // The gallery arrives in a separate request, so keep its place in the page
+        let gallery_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .build();
+        body.append(&gallery_box);
+        Self::load_gallery(&gallery_box, character.mal_id);
+
+        // Notes, lists and tags only apply to saved characters, so follow the favorite state
+        let saved_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(24)
+            .build();
+        Self::load_saved_details(&saved_box, character.mal_id);
+        let model = FavoritesModel::shared();
+        let subscription = model.subscribe({
+            let saved_box = saved_box.downgrade();
+            let mal_id = character.mal_id;
+            move |change| {
+                let Some(saved_box) = saved_box.upgrade() else {
+                    return;
+                };
+                match change {
+                    // Edits made on this page are already on screen
+                    FavoritesChange::DetailsChanged(_) => {}
+                    // Lists may have been added, renamed or removed elsewhere
+                    FavoritesChange::CollectionsChanged => Self::reload_collections(&saved_box, mal_id),
+                    change if change.affects(mal_id) => Self::load_saved_details(&saved_box, mal_id),
+                    _ => {}
+                }
+            }
+        });
+        saved_box.connect_destroy(move |_| model.unsubscribe(subscription));
+        body.append(&saved_box);
+
+        if !full.anime.is_empty() {
+            let rows = full.anime.iter().map(|entry| (&entry.anime, entry.role.as_str()));
+            body.append(&Self::create_appearances_group(SeriesKind::Anime, rows));
+        }
+
+        if !full.manga.is_empty() {
+            let rows = full.manga.iter().map(|entry| (&entry.manga, entry.role.as_str()));
+            body.append(&Self::create_appearances_group(SeriesKind::Manga, rows));
+        }
+
+        // Voice actors come from their own endpoint, like the gallery
+        let voices_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .build();
+        body.append(&voices_box);
+        Self::load_voices(&voices_box, character.mal_id);
+    }
+
+    fn load_voices(voices_box: &Box, mal_id: u32) {
+        let voices_box = voices_box.downgrade();
+        glib::MainContext::default().spawn_local(async move {
+            let voices = match ApiHandler::new().get_character_voices(mal_id).await {
+                Ok(voices) => voices,
+                Err(e) => {
+                    warn!("Failed to load voice actors of {}: {}", mal_id, e);
+                    return;
+                }
+            };
+
+            if let Some(voices_box) = voices_box.upgrade()
+                && !voices.is_empty()
+            {
+                voices_box.append(&Self::create_voices_group(&voices));
+            }
+        });
+    }
+
+    fn load_gallery(gallery_box: &Box, mal_id: u32) {
+        let gallery_box = gallery_box.downgrade();
+        glib::MainContext::default().spawn_local(async move {
+            let pictures = match ApiHandler::new().get_character_pictures(mal_id).await {
+                Ok(pictures) => pictures,
+                Err(e) => {
+                    warn!("Failed to load pictures of {}: {}", mal_id, e);
+                    return;
+                }
+            };
+
+            let urls: Vec<String> = pictures
+                .iter()
+                .filter_map(|picture| picture.url().map(str::to_string))
+                .collect();
+            if let Some(gallery_box) = gallery_box.upgrade()
+                && !urls.is_empty()
+            {
+                gallery_box.append(&PictureGallery::new(mal_id, urls).widget);
+            }
+        });
+    }
+
+    fn load_saved_details(saved_box: &Box, mal_id: u32) {
+        while let Some(child) = saved_box.first_child() {
+            saved_box.remove(&child);
+        }
+
+        if !FavoritesModel::shared().is_favorite(mal_id) {
+            return;
+        }
+
+        match FavoritesStorage::new().get_favorite(mal_id) {
+            Ok(Some(record)) => saved_box.append(&Self::create_notes_group(&record)),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to load favorite {}: {}", mal_id, e),
+        }
+        saved_box.append(&Self::create_collections_group(mal_id));
+    }
+
+    // Swaps in a fresh Collections group, which is always last, leaving the notes alone
+    fn reload_collections(saved_box: &Box, mal_id: u32) {
+        let Some(collections) = saved_box.last_child() else {
+            return;
+        };
+        saved_box.remove(&collections);
+        saved_box.append(&Self::create_collections_group(mal_id));
+    }
+
+    fn create_notes_group(record: &FavoriteRecord) -> adw::PreferencesGroup {
+        let mal_id = record.mal_id();
+        let group = adw::PreferencesGroup::builder()
+            .title("Your Notes")
+            .build();
+
+        let mut ratings = vec!["Not rated".to_string()];
+        ratings.extend((MIN_RATING..=MAX_RATING).map(|rating| rating.to_string()));
+        let rating_labels: Vec<&str> = ratings.iter().map(String::as_str).collect();
+
+        // Item 0 is "Not rated", so the selected index is the rating itself
+        let rating_row = adw::ComboRow::builder()
+            .title("Rating")
+            .model(&gtk::StringList::new(&rating_labels))
+            .selected(record.rating.map(u32::from).unwrap_or(0))
+            .build();
+        rating_row.connect_selected_notify(move |row| {
+            let rating = u8::try_from(row.selected()).ok().filter(|&rating| rating >= MIN_RATING);
+            let row = row.clone();
+            glib::MainContext::default().spawn_local(async move {
+                if let Err(e) = FavoritesModel::shared().set_rating(mal_id, rating).await {
+                    Self::show_error(&row, "save the rating", &e);
+                }
+            });
+        });
+        group.add(&rating_row);
+
+        group.add(&adw::ActionRow::builder()
+            .title("Added")
+            .subtitle(Self::format_timestamp(record.added_at, "%x"))
+            .build());
+
+        let updated_row = adw::ActionRow::builder()
+            .title("Last updated")
+            .subtitle(Self::format_timestamp(record.updated_at, "%x %H:%M"))
+            .build();
+        group.add(&updated_row);
+
+        let notes_view = gtk::TextView::builder()
+            .wrap_mode(gtk::WrapMode::WordChar)
+            .monospace(true)
+            .top_margin(12)
+            .bottom_margin(12)
+            .left_margin(12)
+            .right_margin(12)
+            .height_request(140)
+            .tooltip_text("Markdown is supported: # headings, - lists, **bold**, *italic*, `code` and [links](url)")
+            .build();
+        notes_view.buffer().set_text(&record.notes);
+
+        let notes_frame = gtk::Frame::builder()
+            .child(&notes_view)
+            .margin_top(12)
+            .build();
+        group.add(&notes_frame);
+
+        // The saved notes as they read, below the editor
+        let preview = Label::builder()
+            .wrap(true)
+            .xalign(0.0)
+            .selectable(true)
+            .margin_top(12)
+            .build();
+        Self::show_notes(&preview, &record.notes);
+        group.add(&preview);
+
+        let save_button = Button::builder()
+            .label("Save Notes")
+            .sensitive(false)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+        group.set_header_suffix(Some(&save_button));
+
+        notes_view.buffer().connect_changed({
+            let save_button = save_button.clone();
+            move |_| save_button.set_sensitive(true)
+        });
+
+        save_button.connect_clicked(move |button| {
+            let buffer = notes_view.buffer();
+            let notes = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false).to_string();
+            let button = button.clone();
+            let updated_row = updated_row.clone();
+            let preview = preview.clone();
+            button.set_sensitive(false);
+            glib::MainContext::default().spawn_local(async move {
+                match FavoritesModel::shared().set_notes(mal_id, &notes).await {
+                    Ok(()) => {
+                        updated_row.set_subtitle(&Self::format_timestamp(favorite::unix_now(), "%x %H:%M"));
+                        Self::show_notes(&preview, &notes);
+                    }
+                    Err(e) => {
+                        button.set_sensitive(true);
+                        Self::show_error(&button, "save the notes", &e);
+                    }
+                }
+            });
+        });
+
+        group
+    }
+
+    fn show_notes(preview: &Label, notes: &str) {
+        preview.set_markup(&markdown::to_pango(notes));
+        preview.set_visible(!notes.trim().is_empty());
+    }
+
+    fn format_timestamp(seconds: u64, format: &str) -> String {
+        glib::DateTime::from_unix_local(seconds as i64)
+            .and_then(|date_time| date_time.format(format))
+            .map(|formatted| formatted.to_string())
+            .unwrap_or_default()
+    }
+
+    fn create_collections_group(mal_id: u32) -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::builder()
+            .title("Collections")
+            .description("Lists and tags for this favorite")
+            .build();
+
+        let storage = FavoritesStorage::new();
+        let lists = storage.get_lists().unwrap_or_else(|e| {
+            warn!("Failed to load lists: {}", e);
+            Vec::new()
+        });
+        let containing = storage.get_lists_containing(mal_id).unwrap_or_default();
+
+        if lists.is_empty() {
+            group.add(&adw::ActionRow::builder()
+                .title("No lists yet")
+                .subtitle("Create lists from the sidebar in Your Waifus")
+                .build());
+        }
+
+        for list in lists {
+            let row = adw::SwitchRow::builder()
+                .title(&list.name)
+                .use_markup(false)
+                .active(containing.contains(&list.id))
+                .build();
+            row.connect_active_notify(move |row| {
+                let row = row.clone();
+                glib::MainContext::default().spawn_local(async move {
+                    let member = row.is_active();
+                    if let Err(e) = FavoritesModel::shared().set_list_membership(list.id, mal_id, member).await {
+                        Self::show_error(&row, "update the list", &e);
+                    }
+                });
+            });
+            group.add(&row);
+        }
+
+        let tags = storage.get_tags(mal_id).unwrap_or_default();
+        let tags_row = adw::EntryRow::builder()
+            .title("Tags, separated by commas")
+            .text(tags.join(", "))
+            .show_apply_button(true)
+            .build();
+        tags_row.connect_apply(move |row| {
+            let tags: Vec<String> = row.text().split(',').map(|tag| tag.trim().to_string()).collect();
+            let row = row.clone();
+            glib::MainContext::default().spawn_local(async move {
+                if let Err(e) = FavoritesModel::shared().set_tags(mal_id, tags).await {
+                    Self::show_error(&row, "save the tags", &e);
+                }
+            });
+        });
+        group.add(&tags_row);
+
+        group
+    }
+
+    fn show_error(widget: &impl IsA<gtk::Widget>, action: &str, error: &str) {
+        error!("Failed to {}: {}", action, error);
+        if let Some(overlay) = toast::overlay_for(widget) {
+            overlay.add_toast(toast::plain_toast(&format!("Could not {}: {}", action, error)));
+        }
+    }
+
+    // Rows open the person page listing everyone else they voiced
+    fn create_voices_group(voices: &[CharacterVoice]) -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::builder()
+            .title("Voice Actors")
+            .build();
+
+        for voice in voices {
+            group.add(&Self::create_link_row(&voice.person.name, &voice.language, "win.show-person", voice.person.mal_id));
+        }
+
+        group
+    }
+
+    // Rows open the series page with its full cast
+    fn create_appearances_group<'a>(kind: SeriesKind, rows: impl Iterator<Item = (&'a MediaEntry, &'a str)>) -> adw::PreferencesGroup {
+        let group = adw::PreferencesGroup::builder()
+            .title(kind.label())
+            .build();
+
+        for (series, role) in rows {
+            group.add(&Self::create_link_row(&series.title, role, kind.action_name(), series.mal_id));
+        }
+
+        group
+    }
+
+    // A row that activates `action_name` with `mal_id` to open another page
+    fn create_link_row(title: &str, subtitle: &str, action_name: &str, mal_id: u32) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .title(title)
+            .subtitle(subtitle)
+            .use_markup(false)
+            .activatable(true)
+            .action_name(action_name)
+            .action_target(&mal_id.to_variant())
+            .build();
+        row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+        row
+    }
+}
@@ -1,15 +1,48 @@
 use libadwaita::gtk;
 use gtk::prelude::*;
-use gtk::{ScrolledWindow, FlowBox, SelectionMode, Box, Orientation, Button, Align, Entry, Spinner};
+use gtk::{ScrolledWindow, FlowBox, SelectionMode, Box, Orientation, Button, Align, Entry, Spinner, DropDown, Expander, Label, SpinButton, CheckButton, ListBox, Popover, PositionType, Stack, StackSwitcher};
+
+use crate::api::search_query::{CharacterOrderBy, CharacterSearchQuery, SortDirection, MAX_LIMIT};
+use crate::ui::pages::series_browser::SeriesBrowser;
+use crate::ui::utils::app_settings::AppSettings;
+
+// Choices of the "Order by" dropdown after the leading "Relevance" entry
+const ORDER_CHOICES: [(&str, CharacterOrderBy); 3] = [
+    ("Favorites", CharacterOrderBy::Favorites),
+    ("Name", CharacterOrderBy::Name),
+    ("MAL ID", CharacterOrderBy::MalId),
+];
+const SORT_CHOICES: [(&str, SortDirection); 2] = [
+    ("Descending", SortDirection::Desc),
+    ("Ascending", SortDirection::Asc),
+];
 
 #[derive(Clone)]
 pub struct ExplorePage {
-    pub container: ScrolledWindow,
+    pub container: Box,
+    // Switches between browsing characters and browsing series
+    pub mode_stack: Stack,
+    pub characters_window: ScrolledWindow,
+    pub series_browser: SeriesBrowser,
     pub character_container: FlowBox,
     pub fetch_button: Button,
+    pub top_button: Button,
+    pub exclude_favorites_check: CheckButton,
     pub search_entry: Entry,
     pub search_button: Button,
+    pub save_search_button: Button,
+    // Recent searches shown under the entry while typing
+    pub history_popover: Popover,
+    pub history_list: ListBox,
+    // Saved searches as chips below the search bar
+    pub saved_searches_box: FlowBox,
+    pub filter_expander: Expander,
+    pub order_by_dropdown: DropDown,
+    pub sort_dropdown: DropDown,
+    pub letter_dropdown: DropDown,
+    pub limit_spin: SpinButton,
     pub loading_spinner: Spinner,
+    pub load_more_spinner: Spinner,
 }
 
 impl ExplorePage {
@@ -24,6 +57,43 @@ impl ExplorePage {
             .tooltip_text("Search")
             .build();
 
+        let save_search_button = Button::builder()
+            .icon_name("view-pin-symbolic")
+            .tooltip_text("Save this search")
+            .build();
+
+        let history_list = ListBox::builder()
+            .selection_mode(SelectionMode::None)
+            .css_classes(vec!["navigation-sidebar".to_string()])
+            .build();
+        // Clicking a suggestion must leave keyboard focus in the entry
+        history_list.set_can_focus(false);
+
+        // Not autohiding, so the popover never takes focus away from the entry while typing
+        let history_popover = Popover::builder()
+            .child(&history_list)
+            .position(PositionType::Bottom)
+            .halign(Align::Start)
+            .autohide(false)
+            .has_arrow(false)
+            .build();
+        history_popover.set_parent(&search_entry);
+        search_entry.connect_destroy({
+            let history_popover = history_popover.clone();
+            move |_| history_popover.unparent()
+        });
+
+        let saved_searches_box = FlowBox::builder()
+            .selection_mode(SelectionMode::None)
+            .column_spacing(6)
+            .row_spacing(6)
+            .max_children_per_line(20)
+            .margin_start(20)
+            .margin_end(20)
+            .margin_bottom(10)
+            .visible(false)
+            .build();
+
         let search_box = Box::builder()
             .orientation(Orientation::Horizontal)
             .spacing(10)
@@ -35,14 +105,82 @@ impl ExplorePage {
         
         search_box.append(&search_entry);
         search_box.append(&search_button);
+        search_box.append(&save_search_button);
+
+        let mut order_labels = vec!["Relevance"];
+        order_labels.extend(ORDER_CHOICES.iter().map(|(label, _)| *label));
+        let order_by_dropdown = DropDown::from_strings(&order_labels);
+
+        let sort_labels: Vec<&str> = SORT_CHOICES.iter().map(|(label, _)| *label).collect();
+        let sort_dropdown = DropDown::from_strings(&sort_labels);
+        // Direction only means something once an order is picked
+        sort_dropdown.set_sensitive(false);
+        order_by_dropdown.connect_selected_notify({
+            let sort_dropdown = sort_dropdown.clone();
+            move |dropdown| sort_dropdown.set_sensitive(dropdown.selected() > 0)
+        });
+
+        let letters: Vec<String> = std::iter::once("Any".to_string())
+            .chain(('A'..='Z').map(|letter| letter.to_string()))
+            .collect();
+        let letter_labels: Vec<&str> = letters.iter().map(String::as_str).collect();
+        let letter_dropdown = DropDown::from_strings(&letter_labels);
+
+        let limit_spin = SpinButton::with_range(1.0, MAX_LIMIT as f64, 1.0);
+        limit_spin.set_value(AppSettings::shared().results_per_page() as f64);
+
+        let filter_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .margin_top(10)
+            .build();
+
+        for (title, control) in [
+            ("Order by", order_by_dropdown.upcast_ref::<gtk::Widget>()),
+            ("Direction", sort_dropdown.upcast_ref()),
+            ("Starts with", letter_dropdown.upcast_ref()),
+            ("Per page", limit_spin.upcast_ref()),
+        ] {
+            filter_box.append(&Label::builder()
+                .label(title)
+                .css_classes(vec!["dim-label".to_string()])
+                .build());
+            filter_box.append(control);
+        }
+
+        let filter_expander = Expander::builder()
+            .label("Filters")
+            .child(&filter_box)
+            .margin_start(20)
+            .margin_end(20)
+            .build();
 
         let fetch_button = Button::builder()
-            .label("Search Waifu Random")
+            .label("Random Waifus")
+            .tooltip_text("Roll a batch of random characters")
+            .build();
+
+        let top_button = Button::builder()
+            .label("Top Waifus")
+            .build();
+
+        let exclude_favorites_check = CheckButton::builder()
+            .label("Skip favorites")
+            .tooltip_text("Leave characters you already saved out of random rolls")
+            .build();
+
+        let discover_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
             .halign(Align::Center)
             .margin_top(15)
             .margin_bottom(15)
             .build();
 
+        discover_box.append(&fetch_button);
+        discover_box.append(&top_button);
+        discover_box.append(&exclude_favorites_check);
+
         let loading_spinner = Spinner::builder()
             .halign(Align::Center)
             .valign(Align::Center)
@@ -68,29 +206,138 @@ impl ExplorePage {
             .vexpand(true)
             .build();
 
+        let load_more_spinner = Spinner::builder()
+            .halign(Align::Center)
+            .width_request(32)
+            .height_request(32)
+            .margin_bottom(20)
+            .visible(false)
+            .build();
+
         let search_page_box = Box::builder()
             .orientation(Orientation::Vertical)
             .vexpand(true)
             .build();
         
         search_page_box.append(&search_box);
-        search_page_box.append(&fetch_button);
+        search_page_box.append(&saved_searches_box);
+        search_page_box.append(&filter_expander);
+        search_page_box.append(&discover_box);
         search_page_box.append(&loading_spinner);
         search_page_box.append(&character_container);
+        search_page_box.append(&load_more_spinner);
 
-        let container = ScrolledWindow::builder()
+        let characters_window = ScrolledWindow::builder()
             .vexpand(true)
             .hexpand(true)
             .child(&search_page_box)
             .build();
 
+        let series_browser = SeriesBrowser::new();
+
+        let mode_stack = Stack::builder()
+            .transition_type(gtk::StackTransitionType::Crossfade)
+            .vexpand(true)
+            .build();
+        mode_stack.add_titled(&characters_window, Some("characters"), "Characters");
+        mode_stack.add_titled(&series_browser.container, Some("series"), "Anime & Manga");
+
+        let mode_switcher = StackSwitcher::builder()
+            .stack(&mode_stack)
+            .halign(Align::Center)
+            .margin_top(10)
+            .build();
+
+        let container = Box::builder()
+            .orientation(Orientation::Vertical)
+            .build();
+        container.append(&mode_switcher);
+        container.append(&mode_stack);
+
         Self {
             container,
+            mode_stack,
+            characters_window,
+            series_browser,
             character_container,
             fetch_button,
+            top_button,
+            exclude_favorites_check,
             search_entry,
             search_button,
+            save_search_button,
+            history_popover,
+            history_list,
+            saved_searches_box,
+            filter_expander,
+            order_by_dropdown,
+            sort_dropdown,
+            letter_dropdown,
+            limit_spin,
             loading_spinner,
+            load_more_spinner,
+        }
+    }
+
+    // The search described by the entry text and the filter panel
+    pub fn search_query(&self) -> CharacterSearchQuery {
+        let mut query = CharacterSearchQuery::new()
+            .query(&self.search_entry.text())
+            .limit(self.limit_spin.value_as_int() as u32);
+
+        // Index 0 is "Relevance", which leaves ordering to Jikan
+        if let Some((_, order_by)) = (self.order_by_dropdown.selected() as usize)
+            .checked_sub(1)
+            .and_then(|index| ORDER_CHOICES.get(index))
+        {
+            query = query.order_by(*order_by);
+            if let Some((_, sort)) = SORT_CHOICES.get(self.sort_dropdown.selected() as usize) {
+                query = query.sort(*sort);
+            }
         }
+
+        // Index 0 is "Any"
+        if let Some(letter) = (self.letter_dropdown.selected() as usize)
+            .checked_sub(1)
+            .and_then(|index| ('A'..='Z').nth(index))
+        {
+            query = query.letter(letter);
+        }
+
+        query
+    }
+
+    // Sets the entry and filter panel to describe `query`
+    pub fn apply_search_query(&self, query: &CharacterSearchQuery) {
+        self.search_entry.set_text(query.text().unwrap_or_default());
+        self.search_entry.set_position(-1);
+
+        let order_index = query
+            .ordering()
+            .and_then(|order_by| ORDER_CHOICES.iter().position(|(_, choice)| *choice == order_by))
+            .map_or(0, |index| index + 1);
+        self.order_by_dropdown.set_selected(order_index as u32);
+
+        let sort_index = query
+            .direction()
+            .and_then(|sort| SORT_CHOICES.iter().position(|(_, choice)| *choice == sort))
+            .unwrap_or(0);
+        self.sort_dropdown.set_selected(sort_index as u32);
+
+        let letter_index = query
+            .starting_letter()
+            .and_then(|letter| letter.chars().next())
+            .and_then(|letter| ('A'..='Z').position(|choice| choice == letter))
+            .map_or(0, |index| index + 1);
+        self.letter_dropdown.set_selected(letter_index as u32);
+
+        let page_size = query.page_size().unwrap_or_else(|| AppSettings::shared().results_per_page());
+        self.limit_spin.set_value(page_size as f64);
+    }
+}
+
+impl Default for ExplorePage {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -1,14 +1,121 @@
+use libadwaita as adw;
 use libadwaita::gtk;
-use gtk::prelude::*;
-use gtk::{ScrolledWindow, FlowBox, SelectionMode, Align, Image, Box, Orientation};
+use adw::prelude::*;
+use gtk::{gio, glib, ScrolledWindow, FlowBox, SelectionMode, Align, Image, Box, Orientation, Button, Label, ListBox, ListBoxRow};
+use log::{error, warn};
+use std::cell::{Cell, RefCell};
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashSet;
+use std::rc::Rc;
 
-use crate::storage::favorites::FavoritesStorage;
+use crate::models::collection::CustomList;
+use crate::models::favorite::FavoriteRecord;
+use crate::storage::favorites::{FavoritesStorage, SyncReport};
 use crate::ui::character_widget::CharacterWidget;
+use crate::ui::favorites_model::FavoritesModel;
+use crate::ui::utils::image_loader::ImageLoader;
+use crate::ui::utils::list_diff;
+use crate::ui::utils::toast;
+
+// What the sidebar currently narrows the favorites down to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FavoritesFilter {
+    All,
+    List(u32),
+    Tag(String),
+}
+
+// Orders offered by the sort dropdown, in dropdown order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FavoritesSort {
+    DateAdded,
+    Rating,
+    Name,
+    KanjiName,
+    MalFavorites,
+}
+
+impl FavoritesSort {
+    const ALL: [FavoritesSort; 5] = [
+        FavoritesSort::DateAdded,
+        FavoritesSort::Rating,
+        FavoritesSort::Name,
+        FavoritesSort::KanjiName,
+        FavoritesSort::MalFavorites,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            FavoritesSort::DateAdded => "Recently Added",
+            FavoritesSort::Rating => "Highest Rated",
+            FavoritesSort::Name => "Name",
+            FavoritesSort::KanjiName => "Kanji Name",
+            FavoritesSort::MalFavorites => "Most Favorited on MAL",
+        }
+    }
+
+    // Equal records keep the store's order, which is newest first
+    fn compare(self, a: &FavoriteRecord, b: &FavoriteRecord) -> Ordering {
+        match self {
+            FavoritesSort::DateAdded => Reverse(a.added_at).cmp(&Reverse(b.added_at)),
+            // Unrated favorites go last
+            FavoritesSort::Rating => Reverse(a.rating).cmp(&Reverse(b.rating)),
+            FavoritesSort::Name => a.character.name.to_lowercase().cmp(&b.character.name.to_lowercase()),
+            // Characters without a kanji name go last
+            FavoritesSort::KanjiName => match (Self::kanji_name(a), Self::kanji_name(b)) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            FavoritesSort::MalFavorites => Reverse(a.character.favorites).cmp(&Reverse(b.character.favorites)),
+        }
+    }
+
+    fn kanji_name(record: &FavoriteRecord) -> Option<&str> {
+        record.character.name_kanji.as_deref().filter(|name| !name.is_empty())
+    }
+}
+
+// Whether the record mentions `query` (already lowercased) in its name, nicknames or about text
+fn matches_query(record: &FavoriteRecord, query: &str) -> bool {
+    let character = &record.character;
+    query.is_empty()
+        || character.name.to_lowercase().contains(query)
+        || character.nicknames.iter().any(|nickname| nickname.to_lowercase().contains(query))
+        || character.about.as_deref().is_some_and(|about| about.to_lowercase().contains(query))
+}
+
+fn record_of(object: &glib::Object) -> Option<FavoriteRecord> {
+    object
+        .downcast_ref::<glib::BoxedAnyObject>()
+        .map(|boxed| boxed.borrow::<FavoriteRecord>().clone())
+}
 
 #[derive(Clone)]
 pub struct FavoritesPage {
-    pub container: ScrolledWindow,
+    pub container: Box,
     pub favorites_container: FlowBox,
+    pub sidebar: ListBox,
+    pub new_list_button: Button,
+    pub sort_dropdown: gtk::DropDown,
+    pub search_entry: gtk::SearchEntry,
+    pub resync_button: Button,
+    pub status_label: Label,
+    content_stack: gtk::Stack,
+    empty_label: Label,
+    // Every saved record, newest first, wrapped in `glib::BoxedAnyObject`
+    store: gio::ListStore,
+    sorter: gtk::CustomSorter,
+    filter: gtk::CustomFilter,
+    filtered_model: gtk::FilterListModel,
+    sort: Rc<Cell<FavoritesSort>>,
+    query: Rc<RefCell<String>>,
+    selection: Rc<RefCell<FavoritesFilter>>,
+    // Ids allowed by the sidebar selection; `None` allows every favorite
+    visible_ids: Rc<RefCell<Option<HashSet<u32>>>>,
+    // Filter chosen by each sidebar row, by row index; `None` for section headings
+    sidebar_filters: Rc<RefCell<Vec<Option<FavoritesFilter>>>>,
 }
 
 impl FavoritesPage {
@@ -28,75 +135,619 @@ impl FavoritesPage {
             .vexpand(true)
             .build();
 
-        let container = ScrolledWindow::builder()
+        let status_label = Label::builder()
+            .hexpand(true)
+            .xalign(0.0)
+            .css_classes(vec!["dim-label".to_string()])
+            .build();
+
+        let resync_button = Button::builder()
+            .child(&adw::ButtonContent::builder()
+                .icon_name("view-refresh-symbolic")
+                .label("Re-sync")
+                .build())
+            .tooltip_text("Refresh saved characters and portraits from Jikan")
+            .build();
+
+        let toolbar = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .margin_start(20)
+            .margin_end(20)
+            .margin_top(20)
+            .margin_bottom(10)
+            .build();
+
+        let sort_labels: Vec<&str> = FavoritesSort::ALL.iter().map(|sort| sort.label()).collect();
+        let sort_dropdown = gtk::DropDown::from_strings(&sort_labels);
+        sort_dropdown.set_tooltip_text(Some("Sort favorites"));
+
+        let search_entry = gtk::SearchEntry::builder()
+            .placeholder_text("Filter favorites")
+            .width_request(240)
+            .build();
+
+        toolbar.append(&search_entry);
+        toolbar.append(&status_label);
+        toolbar.append(&sort_dropdown);
+        toolbar.append(&resync_button);
+
+        let sort = Rc::new(Cell::new(FavoritesSort::DateAdded));
+        let query = Rc::new(RefCell::new(String::new()));
+        let visible_ids: Rc<RefCell<Option<HashSet<u32>>>> = Rc::new(RefCell::new(None));
+
+        let sorter = gtk::CustomSorter::new({
+            let sort = sort.clone();
+            move |a, b| match (record_of(a), record_of(b)) {
+                (Some(a), Some(b)) => sort.get().compare(&a, &b).into(),
+                _ => gtk::Ordering::Equal,
+            }
+        });
+
+        let filter = gtk::CustomFilter::new({
+            let query = query.clone();
+            let visible_ids = visible_ids.clone();
+            move |object| {
+                let Some(record) = record_of(object) else {
+                    return false;
+                };
+                let visible = visible_ids
+                    .borrow()
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(&record.mal_id()));
+                visible && matches_query(&record, &query.borrow())
+            }
+        });
+
+        let store = gio::ListStore::new::<glib::BoxedAnyObject>();
+        let filtered_model = gtk::FilterListModel::new(Some(store.clone()), Some(filter.clone()));
+        let sorted_model = gtk::SortListModel::new(Some(filtered_model.clone()), Some(sorter.clone()));
+
+        favorites_container.bind_model(Some(&sorted_model), |object| {
+            match record_of(object) {
+                Some(record) => CharacterWidget::new(record.character).widget.upcast(),
+                None => Label::new(None).upcast(),
+            }
+        });
+
+        let scrolled_window = ScrolledWindow::builder()
             .vexpand(true)
             .hexpand(true)
             .child(&favorites_container)
             .build();
 
-        Self {
+        // Shown instead of the grid when nothing matches
+        let empty_label = Label::builder()
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .css_classes(vec!["heading".to_string()])
+            .build();
+
+        let empty_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(20)
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .vexpand(true)
+            .build();
+
+        empty_box.append(&Image::builder()
+            .icon_name("ibuki")
+            .pixel_size(64)
+            .build());
+        empty_box.append(&empty_label);
+
+        let content_stack = gtk::Stack::new();
+        content_stack.add_named(&scrolled_window, Some("favorites"));
+        content_stack.add_named(&empty_box, Some("empty"));
+
+        let content_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .hexpand(true)
+            .build();
+
+        content_box.append(&toolbar);
+        content_box.append(&content_stack);
+
+        let sidebar = ListBox::builder()
+            .selection_mode(SelectionMode::Single)
+            .css_classes(vec!["navigation-sidebar".to_string()])
+            .build();
+
+        let new_list_button = Button::builder()
+            .child(&adw::ButtonContent::builder()
+                .icon_name("list-add-symbolic")
+                .label("New List")
+                .build())
+            .margin_start(10)
+            .margin_end(10)
+            .margin_top(10)
+            .margin_bottom(10)
+            .build();
+
+        let sidebar_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .width_request(220)
+            .build();
+
+        sidebar_box.append(&ScrolledWindow::builder()
+            .vexpand(true)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .child(&sidebar)
+            .build());
+        sidebar_box.append(&new_list_button);
+
+        let container = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .build();
+
+        container.append(&sidebar_box);
+        container.append(&gtk::Separator::new(Orientation::Vertical));
+        container.append(&content_box);
+
+        let page = Self {
             container,
             favorites_container,
-        }
+            sidebar,
+            new_list_button,
+            sort_dropdown,
+            search_entry,
+            resync_button,
+            status_label,
+            content_stack,
+            empty_label,
+            store,
+            sorter,
+            filter,
+            filtered_model,
+            sort,
+            query,
+            selection: Rc::new(RefCell::new(FavoritesFilter::All)),
+            visible_ids,
+            sidebar_filters: Rc::new(RefCell::new(Vec::new())),
+        };
+
+        page.connect_resync();
+        page.connect_sidebar();
+        page.connect_sort_and_filter();
+
+        // Refresh whenever favorites, lists or tags change, from this tab or any other
+        FavoritesModel::shared().subscribe({
+            let page = page.clone();
+            move |_| page.load_favorites()
+        });
+
+        page
     }
 
-    pub fn load_favorites(&self) {
-        // Clear existing favorites
-        while let Some(child) = self.favorites_container.first_child() {
-            self.favorites_container.remove(&child);
-        }
+    fn connect_resync(&self) {
+        // Re-syncing needs Jikan, so only offer it while the network is up
+        let network_monitor = gio::NetworkMonitor::default();
+        self.resync_button.set_sensitive(network_monitor.is_network_available());
+        network_monitor.connect_network_changed({
+            let resync_button = self.resync_button.clone();
+            move |_, available| {
+                resync_button.set_sensitive(available);
+            }
+        });
 
-        let storage = FavoritesStorage::new();
-        match storage.get_favorites() {
-            Ok(favorites) => {
-                if favorites.is_empty() {
-                    // Create a container for the "no favorites" message
-                    let no_favorites_box = Box::builder()
-                        .orientation(Orientation::Vertical)
-                        .spacing(20)
-                        .halign(Align::Center)
-                        .valign(Align::Center)
-                        .vexpand(true)
-                        .build();
-                    
-                    // Create heart icon
-                    let heart_icon = Image::builder()
-                        .icon_name("ibuki")
-                        .pixel_size(64)
-                        .build();
-                    
-                    // Create bold label
-                    let label = gtk::Label::builder()
-                        .label("You have no favorite waifus yet.")
-                        .halign(Align::Center)
-                        .valign(Align::Center)
-                        .css_classes(vec!["heading".to_string()])
-                        .build();
-                    
-                    no_favorites_box.append(&heart_icon);
-                    no_favorites_box.append(&label);
-                    self.favorites_container.insert(&no_favorites_box, -1);
-                } else {
-                    for character in favorites {
-                        let character_widget = CharacterWidget::new_with_delete_callback(character, {
-                            let clone = self.clone();
-                            move || {
-                                clone.load_favorites();
-                            }
-                        });
-                        self.favorites_container.insert(&character_widget.widget, -1);
+        let page = self.clone();
+        self.resync_button.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            page.status_label.set_label("Syncing favorites...");
+
+            let page = page.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let storage = FavoritesStorage::new();
+                match storage.resync().await {
+                    Ok(report) => page.status_label.set_label(&Self::describe_sync(&report)),
+                    Err(e) => {
+                        warn!("Failed to sync favorites: {}", e);
+                        page.status_label.set_label("Sync failed.");
                     }
                 }
+
+                // Stored portraits may have been replaced on disk
+                ImageLoader::shared().clear_memory_cache();
+                FavoritesModel::shared().reload();
+                page.resync_button.set_sensitive(gio::NetworkMonitor::default().is_network_available());
+            });
+        });
+    }
+
+    fn connect_sidebar(&self) {
+        let page = self.clone();
+        self.sidebar.connect_row_activated(move |_, row| {
+            let filter = page
+                .sidebar_filters
+                .borrow()
+                .get(row.index() as usize)
+                .cloned()
+                .flatten();
+            if let Some(filter) = filter {
+                *page.selection.borrow_mut() = filter;
+                // Only the grid changes; the activated row must outlive its own signal
+                page.update_visible_ids();
+            }
+        });
+
+        let page = self.clone();
+        self.new_list_button.connect_clicked(move |_| {
+            let page = page.clone();
+            page.clone().prompt_list_name("New List", "", "Create", move |name| {
+                let page = page.clone();
+                glib::MainContext::default().spawn_local(async move {
+                    match FavoritesModel::shared().create_list(&name).await {
+                        Ok(list_id) => {
+                            *page.selection.borrow_mut() = FavoritesFilter::List(list_id);
+                            page.load_favorites();
+                        }
+                        Err(e) => page.show_error("create the list", &e),
+                    }
+                });
+            });
+        });
+    }
+
+    fn load_sidebar(&self) {
+        let storage = FavoritesStorage::new();
+        let lists = storage.get_lists().unwrap_or_else(|e| {
+            warn!("Failed to load lists: {}", e);
+            Vec::new()
+        });
+        let tags = storage.get_all_tags().unwrap_or_else(|e| {
+            warn!("Failed to load tags: {}", e);
+            Vec::new()
+        });
+
+        // Fall back to every favorite once the selected list or tag is gone
+        let still_exists = match &*self.selection.borrow() {
+            FavoritesFilter::All => true,
+            FavoritesFilter::List(list_id) => lists.iter().any(|list| list.id == *list_id),
+            FavoritesFilter::Tag(tag) => tags.contains(tag),
+        };
+        if !still_exists {
+            *self.selection.borrow_mut() = FavoritesFilter::All;
+        }
+
+        while let Some(child) = self.sidebar.first_child() {
+            self.sidebar.remove(&child);
+        }
+
+        let mut filters = Vec::new();
+        self.append_sidebar_row(&mut filters, &Self::create_sidebar_label("All Favorites", "starred-symbolic"), Some(FavoritesFilter::All));
+
+        if !lists.is_empty() {
+            self.append_sidebar_row(&mut filters, &Self::create_sidebar_heading("Lists"), None);
+            for (position, list) in lists.iter().enumerate() {
+                let row_content = self.create_list_row(list, position, lists.len());
+                self.append_sidebar_row(&mut filters, &row_content, Some(FavoritesFilter::List(list.id)));
             }
+        }
+
+        if !tags.is_empty() {
+            self.append_sidebar_row(&mut filters, &Self::create_sidebar_heading("Tags"), None);
+            for tag in tags {
+                let row_content = Self::create_sidebar_label(&tag, "bookmark-new-symbolic");
+                self.append_sidebar_row(&mut filters, &row_content, Some(FavoritesFilter::Tag(tag)));
+            }
+        }
+
+        let current = self.selection.borrow().clone();
+        let selected = filters.iter().position(|filter| filter.as_ref() == Some(&current));
+        *self.sidebar_filters.borrow_mut() = filters;
+
+        if let Some(index) = selected {
+            self.sidebar.select_row(self.sidebar.row_at_index(index as i32).as_ref());
+        }
+    }
+
+    fn append_sidebar_row(&self, filters: &mut Vec<Option<FavoritesFilter>>, content: &impl IsA<gtk::Widget>, filter: Option<FavoritesFilter>) {
+        let row = ListBoxRow::builder()
+            .child(content)
+            .selectable(filter.is_some())
+            .activatable(filter.is_some())
+            .build();
+        self.sidebar.append(&row);
+        filters.push(filter);
+    }
+
+    fn create_sidebar_label(title: &str, icon_name: &str) -> Box {
+        let row_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .build();
+
+        row_box.append(&Image::from_icon_name(icon_name));
+        row_box.append(&Label::builder()
+            .label(title)
+            .xalign(0.0)
+            .hexpand(true)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build());
+        row_box
+    }
+
+    fn create_sidebar_heading(title: &str) -> Label {
+        Label::builder()
+            .label(title)
+            .xalign(0.0)
+            .margin_top(10)
+            .css_classes(vec!["heading".to_string(), "dim-label".to_string()])
+            .build()
+    }
+
+    fn create_list_row(&self, list: &CustomList, position: usize, list_count: usize) -> Box {
+        let row_box = Self::create_sidebar_label(&list.name, "view-list-symbolic");
+
+        let menu_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(2)
+            .build();
+
+        let popover = gtk::Popover::builder()
+            .child(&menu_box)
+            .build();
+
+        let menu_button = gtk::MenuButton::builder()
+            .icon_name("view-more-symbolic")
+            .popover(&popover)
+            .tooltip_text("List options")
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let list_id = list.id;
+        let list_name = list.name.clone();
+
+        let rename_button = Self::create_menu_item("Rename…");
+        rename_button.connect_clicked({
+            let page = self.clone();
+            let popover = popover.clone();
+            move |_| {
+                popover.popdown();
+                let page = page.clone();
+                page.clone().prompt_list_name("Rename List", &list_name, "Rename", move |name| {
+                    let page = page.clone();
+                    glib::MainContext::default().spawn_local(async move {
+                        if let Err(e) = FavoritesModel::shared().rename_list(list_id, &name).await {
+                            page.show_error("rename the list", &e);
+                        }
+                    });
+                });
+            }
+        });
+        menu_box.append(&rename_button);
+
+        let move_up_button = Self::create_menu_item("Move Up");
+        move_up_button.set_sensitive(position > 0);
+        self.connect_move(&move_up_button, &popover, list_id, position.saturating_sub(1));
+        menu_box.append(&move_up_button);
+
+        let move_down_button = Self::create_menu_item("Move Down");
+        move_down_button.set_sensitive(position + 1 < list_count);
+        self.connect_move(&move_down_button, &popover, list_id, position + 1);
+        menu_box.append(&move_down_button);
+
+        let delete_button = Self::create_menu_item("Delete…");
+        delete_button.add_css_class("error");
+        delete_button.connect_clicked({
+            let page = self.clone();
+            let popover = popover.clone();
+            let list_name = list.name.clone();
+            move |_| {
+                popover.popdown();
+                page.confirm_delete_list(list_id, &list_name);
+            }
+        });
+        menu_box.append(&delete_button);
+
+        row_box.append(&menu_button);
+        row_box
+    }
+
+    fn create_menu_item(label: &str) -> Button {
+        Button::builder()
+            .child(&Label::builder().label(label).xalign(0.0).build())
+            .css_classes(vec!["flat".to_string()])
+            .build()
+    }
+
+    fn connect_move(&self, button: &Button, popover: &gtk::Popover, list_id: u32, position: usize) {
+        let page = self.clone();
+        let popover = popover.clone();
+        button.connect_clicked(move |_| {
+            popover.popdown();
+            let page = page.clone();
+            glib::MainContext::default().spawn_local(async move {
+                if let Err(e) = FavoritesModel::shared().move_list(list_id, position).await {
+                    page.show_error("move the list", &e);
+                }
+            });
+        });
+    }
+
+    // Asks for a list name and passes the trimmed, non-empty result to `on_confirm`
+    fn prompt_list_name<F>(self, heading: &str, initial_name: &str, confirm_label: &str, on_confirm: F)
+    where
+        F: Fn(String) + 'static,
+    {
+        let entry = gtk::Entry::builder()
+            .text(initial_name)
+            .placeholder_text("List name")
+            .activates_default(true)
+            .build();
+
+        let dialog = adw::MessageDialog::builder()
+            .heading(heading)
+            .extra_child(&entry)
+            .modal(true)
+            .build();
+        dialog.set_transient_for(self.container.root().and_downcast::<gtk::Window>().as_ref());
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("confirm", confirm_label);
+        dialog.set_response_appearance("confirm", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("confirm"));
+        dialog.set_close_response("cancel");
+        dialog.set_response_enabled("confirm", !initial_name.trim().is_empty());
+
+        entry.connect_changed({
+            let dialog = dialog.clone();
+            move |entry| dialog.set_response_enabled("confirm", !entry.text().trim().is_empty())
+        });
+
+        dialog.connect_response(Some("confirm"), move |_, _| {
+            let name = entry.text().trim().to_string();
+            if !name.is_empty() {
+                on_confirm(name);
+            }
+        });
+
+        dialog.present();
+    }
+
+    fn confirm_delete_list(&self, list_id: u32, list_name: &str) {
+        let dialog = adw::MessageDialog::builder()
+            .heading(format!("Delete “{}”?", list_name))
+            .body("The characters in it stay in your favorites.")
+            .modal(true)
+            .build();
+        dialog.set_transient_for(self.container.root().and_downcast::<gtk::Window>().as_ref());
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("delete", "Delete");
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_close_response("cancel");
+
+        let page = self.clone();
+        dialog.connect_response(Some("delete"), move |_, _| {
+            let page = page.clone();
+            glib::MainContext::default().spawn_local(async move {
+                if let Err(e) = FavoritesModel::shared().delete_list(list_id).await {
+                    page.show_error("delete the list", &e);
+                }
+            });
+        });
+
+        dialog.present();
+    }
+
+    fn show_error(&self, action: &str, error: &str) {
+        error!("Failed to {}: {}", action, error);
+        if let Some(overlay) = toast::overlay_for(&self.container) {
+            overlay.add_toast(toast::plain_toast(&format!("Could not {}: {}", action, error)));
+        }
+    }
+
+    fn describe_sync(report: &SyncReport) -> String {
+        let mut summary = format!("Synced {} favorites", report.updated);
+        if report.failed > 0 {
+            summary.push_str(&format!(", {} could not be refreshed", report.failed));
+        }
+        if report.failed_images > 0 {
+            summary.push_str(&format!(", {} portraits failed to download", report.failed_images));
+        }
+        summary.push('.');
+        summary
+    }
+
+    fn connect_sort_and_filter(&self) {
+        let page = self.clone();
+        self.sort_dropdown.connect_selected_notify(move |dropdown| {
+            let sort = FavoritesSort::ALL
+                .get(dropdown.selected() as usize)
+                .copied()
+                .unwrap_or(FavoritesSort::DateAdded);
+            page.sort.set(sort);
+            page.sorter.changed(gtk::SorterChange::Different);
+        });
+
+        let page = self.clone();
+        self.search_entry.connect_search_changed(move |entry| {
+            *page.query.borrow_mut() = entry.text().trim().to_lowercase();
+            page.filter.changed(gtk::FilterChange::Different);
+            page.update_empty_state();
+        });
+    }
+
+    pub fn load_favorites(&self) {
+        self.load_sidebar();
+        self.load_characters();
+    }
+
+    // Brings the store in line with storage, touching only the records that changed or moved
+    fn load_characters(&self) {
+        let mut favorites = match FavoritesStorage::new().get_favorites() {
+            Ok(favorites) => favorites,
             Err(e) => {
-                eprintln!("Failed to load favorites: {}", e);
-                let label = gtk::Label::builder()
-                    .label("Failed to load favorites.")
-                    .halign(Align::Center)
-                    .valign(Align::Center)
-                    .build();
-                self.favorites_container.insert(&label, -1);
+                error!("Failed to load favorites: {}", e);
+                self.store.remove_all();
+                self.empty_label.set_label("Failed to load favorites.");
+                self.content_stack.set_visible_child_name("empty");
+                return;
             }
+        };
+        // Newest first, so records that sort equal show the latest addition first
+        favorites.reverse();
+
+        let current: Vec<FavoriteRecord> = (0..self.store.n_items())
+            .filter_map(|position| self.store.item(position).as_ref().and_then(record_of))
+            .collect();
+        for splice in list_diff::splices(&current, &favorites, FavoriteRecord::mal_id) {
+            let added: Vec<glib::BoxedAnyObject> = splice.added.into_iter().map(glib::BoxedAnyObject::new).collect();
+            self.store.splice(splice.position, splice.removed, &added);
         }
+
+        self.update_visible_ids();
+    }
+
+    // Recomputes which favorites the sidebar selection allows, e.g. after it changed or a list was edited
+    fn update_visible_ids(&self) {
+        let storage = FavoritesStorage::new();
+        let ids: Result<Option<HashSet<u32>>, String> = match &*self.selection.borrow() {
+            FavoritesFilter::All => Ok(None),
+            FavoritesFilter::List(list_id) => storage
+                .get_list_members(*list_id)
+                .map(|records| Some(records.iter().map(FavoriteRecord::mal_id).collect())),
+            FavoritesFilter::Tag(tag) => storage
+                .get_tagged(tag)
+                .map(|records| Some(records.iter().map(FavoriteRecord::mal_id).collect())),
+        };
+
+        let ids = ids.unwrap_or_else(|e| {
+            warn!("Failed to load favorites for the selection: {}", e);
+            Some(HashSet::new())
+        });
+
+        if *self.visible_ids.borrow() != ids {
+            *self.visible_ids.borrow_mut() = ids;
+            self.filter.changed(gtk::FilterChange::Different);
+        }
+        self.update_empty_state();
+    }
+
+    fn update_empty_state(&self) {
+        if self.filtered_model.n_items() > 0 {
+            self.content_stack.set_visible_child_name("favorites");
+            return;
+        }
+
+        let message = if self.store.n_items() == 0 {
+            "You have no favorite waifus yet."
+        } else if !self.query.borrow().is_empty() {
+            "No favorites match your filter."
+        } else {
+            match &*self.selection.borrow() {
+                FavoritesFilter::All => "You have no favorite waifus yet.",
+                FavoritesFilter::List(_) => "This list is empty.",
+                FavoritesFilter::Tag(_) => "No favorites carry this tag.",
+            }
+        };
+        self.empty_label.set_label(message);
+        self.content_stack.set_visible_child_name("empty");
+    }
+}
+
+impl Default for FavoritesPage {
+    fn default() -> Self {
+        Self::new()
     }
 }
\ No newline at end of file
@@ -1,2 +1,6 @@
 pub mod explore_page;
-pub mod favorites_page;
\ No newline at end of file
+pub mod favorites_page;
+pub mod character_detail_page;
+pub mod person_page;
+pub mod series_browser;
+pub mod series_detail_page;
\ No newline at end of file
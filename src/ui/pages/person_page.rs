@@ -0,0 +1,301 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::{glib, Align, Box, Button, CheckButton, FlowBox, Image, Label, LinkButton, Orientation, ScrolledWindow, SelectionMode, Spinner};
+use log::{error, warn};
+use std::rc::Rc;
+
+use crate::models::character::Character;
+use crate::models::person::PersonFull;
+use crate::ui::character_widget::CharacterWidget;
+use crate::ui::favorites_model::FavoritesModel;
+use crate::ui::utils::api_handler::ApiHandler;
+use crate::ui::utils::error_display;
+use crate::ui::utils::image_loader::{ImageLoader, ImageSize};
+use crate::ui::utils::labels;
+use crate::ui::utils::toast;
+
+// A character someone voiced, with every anime they voiced it in
+struct VoicedCharacter {
+    character: Character,
+    anime: Vec<String>,
+    main: bool,
+}
+
+// A voice actor and every character they have voiced
+pub struct PersonPage {
+    pub page: adw::NavigationPage,
+}
+
+impl PersonPage {
+    pub fn new(mal_id: u32) -> Self {
+        let loading_spinner = Spinner::builder()
+            .spinning(true)
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .width_request(48)
+            .height_request(48)
+            .vexpand(true)
+            .build();
+
+        let body = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(24)
+            .margin_top(20)
+            .margin_bottom(20)
+            .margin_start(20)
+            .margin_end(20)
+            .build();
+        body.append(&loading_spinner);
+
+        let scrolled_window = ScrolledWindow::builder()
+            .vexpand(true)
+            .hexpand(true)
+            .child(&body)
+            .build();
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        toolbar_view.set_content(Some(&scrolled_window));
+
+        let page = adw::NavigationPage::builder()
+            .title("Voice Actor")
+            .child(&toolbar_view)
+            .build();
+
+        let page_clone = page.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let api_handler = ApiHandler::new();
+            let result = api_handler.get_person_full(mal_id).await;
+            body.remove(&loading_spinner);
+
+            match result {
+                Ok(person) => {
+                    page_clone.set_title(&person.person.name);
+                    Self::populate(&body, &person);
+                }
+                Err(e) => {
+                    warn!("Failed to load person {}: {}", mal_id, e);
+                    body.append(&error_display::for_jikan_error(&e, "Could Not Load Voice Actor"));
+                }
+            }
+        });
+
+        Self { page }
+    }
+
+    fn populate(body: &Box, full: &PersonFull) {
+        body.append(&Self::create_header(full));
+
+        let voiced = Self::group_voices(full);
+        if voiced.is_empty() {
+            body.append(&Label::builder()
+                .label("Jikan lists no voice roles for this person")
+                .css_classes(vec!["dim-label".to_string()])
+                .build());
+            return;
+        }
+
+        let main_only_check = CheckButton::builder()
+            .label("Main roles only")
+            .build();
+
+        let add_all_button = Button::builder()
+            .label("Add All to Favorites")
+            .css_classes(vec!["suggested-action".to_string()])
+            .build();
+
+        let title_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(12)
+            .build();
+        title_box.append(&Label::builder()
+            .label(format!("Voiced Characters ({})", voiced.len()))
+            .xalign(0.0)
+            .hexpand(true)
+            .css_classes(vec!["title-2".to_string()])
+            .build());
+        title_box.append(&main_only_check);
+        title_box.append(&add_all_button);
+        body.append(&title_box);
+
+        let container = FlowBox::builder()
+            .selection_mode(SelectionMode::None)
+            .valign(Align::Start)
+            .column_spacing(20)
+            .row_spacing(20)
+            .min_children_per_line(1)
+            .max_children_per_line(10)
+            .build();
+
+        for entry in &voiced {
+            let card = CharacterWidget::new_partial(entry.character.clone());
+            card.add_caption(&Self::describe_anime(&entry.anime));
+            container.insert(&card.widget, -1);
+        }
+        body.append(&container);
+
+        // Flow box children keep the order of `voiced`
+        let voiced = Rc::new(voiced);
+        container.set_filter_func({
+            let voiced = voiced.clone();
+            let main_only_check = main_only_check.clone();
+            move |child| {
+                !main_only_check.is_active()
+                    || voiced.get(child.index() as usize).is_some_and(|entry| entry.main)
+            }
+        });
+        main_only_check.connect_toggled({
+            let container = container.clone();
+            move |_| container.invalidate_filter()
+        });
+
+        add_all_button.connect_clicked(move |button| {
+            let main_only = main_only_check.is_active();
+            let characters: Vec<Character> = voiced
+                .iter()
+                .filter(|entry| !main_only || entry.main)
+                .map(|entry| entry.character.clone())
+                .collect();
+            Self::add_all(button, characters);
+        });
+    }
+
+    // Collapses the per-anime roles into one entry per character, keeping Jikan's order
+    fn group_voices(full: &PersonFull) -> Vec<VoicedCharacter> {
+        let mut voiced: Vec<VoicedCharacter> = Vec::new();
+        for role in &full.voices {
+            match voiced.iter_mut().find(|entry| entry.character.mal_id == role.character.mal_id) {
+                Some(entry) => {
+                    if !entry.anime.contains(&role.anime.title) {
+                        entry.anime.push(role.anime.title.clone());
+                    }
+                    entry.main |= role.is_main();
+                }
+                None => voiced.push(VoicedCharacter {
+                    character: role.character.to_character(),
+                    anime: vec![role.anime.title.clone()],
+                    main: role.is_main(),
+                }),
+            }
+        }
+        voiced
+    }
+
+    // "Title" or "Title +2 more"
+    fn describe_anime(anime: &[String]) -> String {
+        match anime {
+            [] => String::new(),
+            [only] => only.clone(),
+            [first, rest @ ..] => format!("{} +{} more", first, rest.len()),
+        }
+    }
+
+    // Adds every character that is not saved yet, one after another
+    fn add_all(button: &Button, characters: Vec<Character>) {
+        let model = FavoritesModel::shared();
+        let pending: Vec<Character> = characters
+            .into_iter()
+            .filter(|character| !model.is_favorite(character.mal_id))
+            .collect();
+
+        let overlay = toast::overlay_for(button);
+        if pending.is_empty() {
+            if let Some(overlay) = overlay {
+                overlay.add_toast(toast::plain_toast("All of these characters are already favorites"));
+            }
+            return;
+        }
+
+        let button = button.clone();
+        button.set_sensitive(false);
+        glib::MainContext::default().spawn_local(async move {
+            let total = pending.len();
+            let mut added = 0;
+            let mut failed = 0;
+            for (position, character) in pending.into_iter().enumerate() {
+                button.set_label(&format!("Adding {} of {}...", position + 1, total));
+                // Voice listings only carry a name and picture, as on the single cards
+                match model.add_by_id(character.mal_id).await {
+                    Ok(()) => added += 1,
+                    Err(e) => {
+                        error!("Failed to add favorite {}: {}", character.mal_id, e);
+                        failed += 1;
+                    }
+                }
+            }
+
+            button.set_label("Add All to Favorites");
+            button.set_sensitive(true);
+
+            let mut message = format!("Added {} characters to favorites", added);
+            if failed > 0 {
+                message.push_str(&format!(", {} failed", failed));
+            }
+            if let Some(overlay) = overlay {
+                overlay.add_toast(toast::plain_toast(&message));
+            }
+        });
+    }
+
+    fn create_header(full: &PersonFull) -> Box {
+        let person = &full.person;
+        let header = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(24)
+            .build();
+
+        let photo = Image::builder()
+            .icon_name("avatar-default-symbolic")
+            .pixel_size(96)
+            .valign(Align::Start)
+            .build();
+        photo.set_size_request(180, 270);
+        if let Some(image_url) = &person.images.jpg.image_url {
+            let size = ImageSize::Fit { width: 180, height: 270 };
+            ImageLoader::shared().load_for(&photo, image_url, size, |photo, result| {
+                if let Ok(texture) = result {
+                    photo.set_paintable(Some(&texture));
+                }
+            });
+        }
+        header.append(&photo);
+
+        let details = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .hexpand(true)
+            .build();
+
+        details.append(&labels::text_label(&person.name, &["title-1"]));
+
+        // Japanese names come family name first
+        let native_name: String = [person.family_name.as_deref(), person.given_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect();
+        if !native_name.is_empty() {
+            details.append(&labels::text_label(&native_name, &["title-4", "dim-label"]));
+        }
+
+        let mut facts = Vec::new();
+        if let Some(birthday) = person.birthday.as_deref().and_then(|birthday| birthday.get(..10)) {
+            facts.push(format!("Born {}", birthday));
+        }
+        facts.push(format!("♥ {} favorites", person.favorites));
+        details.append(&labels::text_label(&facts.join(" · "), &["dim-label"]));
+
+        if let Some(about) = &person.about {
+            details.append(&labels::text_label(about, &["body"]));
+        }
+
+        details.append(&LinkButton::builder()
+            .label("View on MyAnimeList")
+            .uri(&person.url)
+            .halign(Align::Start)
+            .build());
+
+        header.append(&details);
+        header
+    }
+}
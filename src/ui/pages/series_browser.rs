@@ -0,0 +1,127 @@
+use libadwaita::gtk;
+use gtk::prelude::*;
+use gtk::{ScrolledWindow, FlowBox, SelectionMode, Box, Orientation, Button, Align, Entry, Spinner, DropDown};
+
+use crate::ui::series_card::SeriesKind;
+
+// The Explore sub-mode for finding an anime or manga and opening its cast
+#[derive(Clone)]
+pub struct SeriesBrowser {
+    pub container: ScrolledWindow,
+    pub kind_dropdown: DropDown,
+    pub search_entry: Entry,
+    pub search_button: Button,
+    pub top_button: Button,
+    pub series_container: FlowBox,
+    pub loading_spinner: Spinner,
+    pub load_more_spinner: Spinner,
+}
+
+impl SeriesBrowser {
+    pub fn new() -> Self {
+        let kind_labels: Vec<&str> = SeriesKind::ALL.iter().map(|kind| kind.label()).collect();
+        let kind_dropdown = DropDown::from_strings(&kind_labels);
+
+        let search_entry = Entry::builder()
+            .placeholder_text("Search for a series...")
+            .hexpand(true)
+            .build();
+
+        let search_button = Button::builder()
+            .icon_name("system-search-symbolic")
+            .tooltip_text("Search")
+            .build();
+
+        let top_button = Button::builder()
+            .label("Top Series")
+            .tooltip_text("Show the highest ranked series")
+            .build();
+
+        let search_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(10)
+            .margin_start(20)
+            .margin_end(20)
+            .margin_top(20)
+            .margin_bottom(10)
+            .build();
+
+        search_box.append(&kind_dropdown);
+        search_box.append(&search_entry);
+        search_box.append(&search_button);
+        search_box.append(&top_button);
+
+        let loading_spinner = Spinner::builder()
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .width_request(48)
+            .height_request(48)
+            .margin_top(20)
+            .margin_bottom(20)
+            .visible(false)
+            .build();
+
+        let series_container = FlowBox::builder()
+            .selection_mode(SelectionMode::None)
+            .halign(gtk::Align::Fill)
+            .valign(gtk::Align::Start)
+            .homogeneous(false)
+            .column_spacing(20)
+            .row_spacing(20)
+            .margin_start(20)
+            .margin_end(20)
+            .margin_bottom(20)
+            .min_children_per_line(1)
+            .max_children_per_line(10)
+            .vexpand(true)
+            .build();
+
+        let load_more_spinner = Spinner::builder()
+            .halign(Align::Center)
+            .width_request(32)
+            .height_request(32)
+            .margin_bottom(20)
+            .visible(false)
+            .build();
+
+        let page_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .vexpand(true)
+            .build();
+
+        page_box.append(&search_box);
+        page_box.append(&loading_spinner);
+        page_box.append(&series_container);
+        page_box.append(&load_more_spinner);
+
+        let container = ScrolledWindow::builder()
+            .vexpand(true)
+            .hexpand(true)
+            .child(&page_box)
+            .build();
+
+        Self {
+            container,
+            kind_dropdown,
+            search_entry,
+            search_button,
+            top_button,
+            series_container,
+            loading_spinner,
+            load_more_spinner,
+        }
+    }
+
+    pub fn kind(&self) -> SeriesKind {
+        SeriesKind::ALL
+            .get(self.kind_dropdown.selected() as usize)
+            .copied()
+            .unwrap_or(SeriesKind::Anime)
+    }
+}
+
+impl Default for SeriesBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
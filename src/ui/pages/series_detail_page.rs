@@ -0,0 +1,226 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::{glib, Align, Box, FlowBox, Image, Label, LinkButton, Orientation, ScrolledWindow, SelectionMode, Spinner};
+use log::warn;
+
+use crate::api::jikan::JikanError;
+use crate::models::character::CastMember;
+use crate::ui::character_widget::CharacterWidget;
+use crate::ui::series_card::{SeriesKind, SeriesSummary};
+use crate::ui::utils::api_handler::ApiHandler;
+use crate::ui::utils::error_display;
+use crate::ui::utils::image_loader::{ImageLoader, ImageSize};
+use crate::ui::utils::labels;
+
+// Everything the page shows about the series itself
+struct SeriesInfo {
+    summary: SeriesSummary,
+    alternative_title: Option<String>,
+    status: Option<String>,
+    synopsis: Option<String>,
+    url: String,
+}
+
+// An anime or manga with its full character cast
+pub struct SeriesDetailPage {
+    pub page: adw::NavigationPage,
+}
+
+impl SeriesDetailPage {
+    pub fn new(kind: SeriesKind, mal_id: u32) -> Self {
+        let loading_spinner = Spinner::builder()
+            .spinning(true)
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .width_request(48)
+            .height_request(48)
+            .vexpand(true)
+            .build();
+
+        let body = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(24)
+            .margin_top(20)
+            .margin_bottom(20)
+            .margin_start(20)
+            .margin_end(20)
+            .build();
+        body.append(&loading_spinner);
+
+        let scrolled_window = ScrolledWindow::builder()
+            .vexpand(true)
+            .hexpand(true)
+            .child(&body)
+            .build();
+
+        let toolbar_view = adw::ToolbarView::new();
+        toolbar_view.add_top_bar(&adw::HeaderBar::new());
+        toolbar_view.set_content(Some(&scrolled_window));
+
+        let page = adw::NavigationPage::builder()
+            .title(kind.label())
+            .child(&toolbar_view)
+            .build();
+
+        let page_clone = page.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let result = Self::fetch_series(kind, mal_id).await;
+            body.remove(&loading_spinner);
+
+            let info = match result {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!("Failed to load {} {}: {}", kind.label(), mal_id, e);
+                    body.append(&error_display::for_jikan_error(&e, "Could Not Load Series"));
+                    return;
+                }
+            };
+            page_clone.set_title(&info.summary.title);
+            body.append(&Self::create_header(&info));
+
+            body.append(&Label::builder()
+                .label("Cast")
+                .xalign(0.0)
+                .css_classes(vec!["title-2".to_string()])
+                .build());
+
+            let cast_spinner = Spinner::builder()
+                .spinning(true)
+                .halign(Align::Center)
+                .width_request(32)
+                .height_request(32)
+                .build();
+            body.append(&cast_spinner);
+
+            let cast = Self::fetch_cast(kind, mal_id).await;
+            body.remove(&cast_spinner);
+            match cast {
+                Ok(cast) if cast.is_empty() => {
+                    body.append(&Label::builder()
+                        .label("Jikan lists no characters for this series")
+                        .css_classes(vec!["dim-label".to_string()])
+                        .build());
+                }
+                Ok(cast) => body.append(&Self::create_cast(cast)),
+                Err(e) => {
+                    warn!("Failed to load cast of {} {}: {}", kind.label(), mal_id, e);
+                    body.append(&error_display::for_jikan_error(&e, "Could Not Load Series"));
+                }
+            }
+        });
+
+        Self { page }
+    }
+
+    async fn fetch_series(kind: SeriesKind, mal_id: u32) -> Result<SeriesInfo, JikanError> {
+        let api_handler = ApiHandler::new();
+        let info = match kind {
+            SeriesKind::Anime => {
+                let anime = api_handler.get_anime(mal_id).await?;
+                SeriesInfo {
+                    summary: SeriesSummary::from_anime(&anime),
+                    alternative_title: anime.title_english.or(anime.title_japanese),
+                    status: anime.status,
+                    synopsis: anime.synopsis,
+                    url: anime.url,
+                }
+            }
+            SeriesKind::Manga => {
+                let manga = api_handler.get_manga(mal_id).await?;
+                SeriesInfo {
+                    summary: SeriesSummary::from_manga(&manga),
+                    alternative_title: manga.title_english.or(manga.title_japanese),
+                    status: manga.status,
+                    synopsis: manga.synopsis,
+                    url: manga.url,
+                }
+            }
+        };
+        Ok(info)
+    }
+
+    async fn fetch_cast(kind: SeriesKind, mal_id: u32) -> Result<Vec<CastMember>, JikanError> {
+        let api_handler = ApiHandler::new();
+        match kind {
+            SeriesKind::Anime => api_handler.get_anime_characters(mal_id).await,
+            SeriesKind::Manga => api_handler.get_manga_characters(mal_id).await,
+        }
+    }
+
+    fn create_header(info: &SeriesInfo) -> Box {
+        let header = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(24)
+            .build();
+
+        let poster = Image::builder()
+            .icon_name("image-missing")
+            .pixel_size(48)
+            .valign(Align::Start)
+            .build();
+        poster.set_size_request(180, 270);
+        if let Some(image_url) = &info.summary.image_url {
+            let size = ImageSize::Fit { width: 180, height: 270 };
+            ImageLoader::shared().load_for(&poster, image_url, size, |poster, result| {
+                if let Ok(texture) = result {
+                    poster.set_paintable(Some(&texture));
+                }
+            });
+        }
+        header.append(&poster);
+
+        let details = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .hexpand(true)
+            .build();
+
+        details.append(&labels::text_label(&info.summary.title, &["title-1"]));
+        if let Some(alternative_title) = &info.alternative_title {
+            details.append(&labels::text_label(alternative_title, &["title-4", "dim-label"]));
+        }
+
+        let mut facts = vec![info.summary.details.clone()];
+        facts.extend(info.status.clone());
+        let facts: Vec<String> = facts.into_iter().filter(|fact| !fact.is_empty()).collect();
+        if !facts.is_empty() {
+            details.append(&labels::text_label(&facts.join(" · "), &["dim-label"]));
+        }
+
+        if let Some(synopsis) = &info.synopsis {
+            details.append(&labels::text_label(synopsis, &["body"]));
+        }
+
+        details.append(&LinkButton::builder()
+            .label("View on MyAnimeList")
+            .uri(&info.url)
+            .halign(Align::Start)
+            .build());
+
+        header.append(&details);
+        header
+    }
+
+    // Main characters first, then supporting ones, each in Jikan's order
+    fn create_cast(mut cast: Vec<CastMember>) -> FlowBox {
+        cast.sort_by_key(|member| !member.is_main());
+
+        let container = FlowBox::builder()
+            .selection_mode(SelectionMode::None)
+            .valign(Align::Start)
+            .column_spacing(20)
+            .row_spacing(20)
+            .min_children_per_line(1)
+            .max_children_per_line(10)
+            .build();
+
+        for member in &cast {
+            let card = CharacterWidget::new_partial(member.to_character());
+            card.add_caption(&member.role);
+            container.insert(&card.widget, -1);
+        }
+
+        container
+    }
+}
@@ -0,0 +1,384 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::{gdk, glib, Box, Button, Label, Orientation, Picture, ScrolledWindow};
+use log::error;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::storage::favorites::FavoritesStorage;
+use crate::ui::favorites_model::FavoritesModel;
+use crate::ui::utils::image_loader::{ImageLoader, ImageSize};
+use crate::ui::utils::toast;
+
+const GALLERY_HEIGHT: i32 = 480;
+const MAX_ZOOM: f64 = 4.0;
+// Factor applied per zoom button press or Ctrl+scroll step
+const ZOOM_STEP: f64 = 1.25;
+
+// One carousel page: a picture that can be zoomed and then panned by dragging
+#[derive(Clone)]
+struct ZoomablePicture {
+    scrolled_window: ScrolledWindow,
+    picture: Picture,
+    carousel: adw::Carousel,
+    zoom: Rc<Cell<f64>>,
+}
+
+// What the picture's own controllers and the carousel's handlers hold, so they do not
+// keep the widgets that own them alive
+#[derive(Clone)]
+struct WeakZoomablePicture {
+    scrolled_window: glib::WeakRef<ScrolledWindow>,
+    picture: glib::WeakRef<Picture>,
+    carousel: glib::WeakRef<adw::Carousel>,
+    zoom: Rc<Cell<f64>>,
+}
+
+impl WeakZoomablePicture {
+    fn upgrade(&self) -> Option<ZoomablePicture> {
+        Some(ZoomablePicture {
+            scrolled_window: self.scrolled_window.upgrade()?,
+            picture: self.picture.upgrade()?,
+            carousel: self.carousel.upgrade()?,
+            zoom: self.zoom.clone(),
+        })
+    }
+}
+
+impl ZoomablePicture {
+    fn new(url: &str, carousel: &adw::Carousel) -> Self {
+        let picture = Picture::builder()
+            .can_shrink(true)
+            .content_fit(gtk::ContentFit::Contain)
+            .hexpand(true)
+            .vexpand(true)
+            .build();
+        ImageLoader::shared().load_for(&picture, url, ImageSize::Original, |picture, result| {
+            if let Ok(texture) = result {
+                picture.set_paintable(Some(&texture));
+            }
+        });
+
+        let scrolled_window = ScrolledWindow::builder()
+            .child(&picture)
+            .hexpand(true)
+            .height_request(GALLERY_HEIGHT)
+            .build();
+
+        let zoomable = Self {
+            scrolled_window,
+            picture,
+            carousel: carousel.clone(),
+            zoom: Rc::new(Cell::new(1.0)),
+        };
+        zoomable.connect_gestures();
+        zoomable
+    }
+
+    fn downgrade(&self) -> WeakZoomablePicture {
+        WeakZoomablePicture {
+            scrolled_window: self.scrolled_window.downgrade(),
+            picture: self.picture.downgrade(),
+            carousel: self.carousel.downgrade(),
+            zoom: self.zoom.clone(),
+        }
+    }
+
+    fn connect_gestures(&self) {
+        let pinch = gtk::GestureZoom::new();
+        let pinch_start = Rc::new(Cell::new(1.0));
+        pinch.connect_begin({
+            let zoom = self.zoom.clone();
+            let pinch_start = pinch_start.clone();
+            move |_, _| pinch_start.set(zoom.get())
+        });
+        pinch.connect_scale_changed({
+            let this = self.downgrade();
+            move |_, scale| {
+                if let Some(this) = this.upgrade() {
+                    this.set_zoom(pinch_start.get() * scale);
+                }
+            }
+        });
+        self.scrolled_window.add_controller(pinch);
+
+        // Ctrl+scroll zooms; plain scrolling is left to the scrolled window
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.set_propagation_phase(gtk::PropagationPhase::Capture);
+        scroll.connect_scroll({
+            let this = self.downgrade();
+            move |controller, _, dy| {
+                if !controller.current_event_state().contains(gdk::ModifierType::CONTROL_MASK) {
+                    return glib::Propagation::Proceed;
+                }
+                let Some(this) = this.upgrade() else {
+                    return glib::Propagation::Proceed;
+                };
+                let factor = if dy < 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+                this.set_zoom(this.zoom.get() * factor);
+                glib::Propagation::Stop
+            }
+        });
+        self.scrolled_window.add_controller(scroll);
+
+        let double_click = gtk::GestureClick::new();
+        double_click.connect_pressed({
+            let this = self.downgrade();
+            move |_, n_press, _, _| {
+                if n_press == 2
+                    && let Some(this) = this.upgrade()
+                {
+                    this.set_zoom(if this.zoom.get() > 1.0 { 1.0 } else { 2.0 });
+                }
+            }
+        });
+        self.scrolled_window.add_controller(double_click);
+
+        // Dragging pans a zoomed picture; at 1x the drag is left to the carousel swipe
+        let drag = gtk::GestureDrag::new();
+        let drag_start = Rc::new(Cell::new((0.0, 0.0)));
+        drag.connect_drag_begin({
+            let zoom = self.zoom.clone();
+            let scrolled_window = self.scrolled_window.downgrade();
+            let drag_start = drag_start.clone();
+            move |gesture, _, _| {
+                let Some(scrolled_window) = scrolled_window.upgrade().filter(|_| zoom.get() > 1.0) else {
+                    gesture.set_state(gtk::EventSequenceState::Denied);
+                    return;
+                };
+                let hadjustment = scrolled_window.hadjustment();
+                let vadjustment = scrolled_window.vadjustment();
+                drag_start.set((hadjustment.value(), vadjustment.value()));
+            }
+        });
+        drag.connect_drag_update({
+            let scrolled_window = self.scrolled_window.downgrade();
+            move |_, dx, dy| {
+                let Some(scrolled_window) = scrolled_window.upgrade() else {
+                    return;
+                };
+                let (start_x, start_y) = drag_start.get();
+                scrolled_window.hadjustment().set_value(start_x - dx);
+                scrolled_window.vadjustment().set_value(start_y - dy);
+            }
+        });
+        self.scrolled_window.add_controller(drag);
+    }
+
+    // Scales the picture around the centre of the view, between 1x and MAX_ZOOM
+    fn set_zoom(&self, zoom: f64) {
+        let zoom = zoom.clamp(1.0, MAX_ZOOM);
+        let previous = self.zoom.replace(zoom);
+        if zoom == previous {
+            return;
+        }
+
+        if zoom == 1.0 {
+            self.picture.set_size_request(-1, -1);
+        } else {
+            let width = self.scrolled_window.width() as f64 * zoom;
+            let height = self.scrolled_window.height() as f64 * zoom;
+            self.picture.set_size_request(width as i32, height as i32);
+        }
+        // Swiping to another picture would fight with panning this one
+        self.carousel.set_interactive(zoom == 1.0);
+
+        // The adjustments only know the new size after the next layout
+        let ratio = zoom / previous;
+        let hadjustment = self.scrolled_window.hadjustment();
+        let vadjustment = self.scrolled_window.vadjustment();
+        let center_x = hadjustment.value() + hadjustment.page_size() / 2.0;
+        let center_y = vadjustment.value() + vadjustment.page_size() / 2.0;
+        glib::idle_add_local_once(move || {
+            hadjustment.set_value(center_x * ratio - hadjustment.page_size() / 2.0);
+            vadjustment.set_value(center_y * ratio - vadjustment.page_size() / 2.0);
+        });
+    }
+}
+
+// Swipeable gallery of a character's pictures. For favorites, any picture can be
+// chosen as the card thumbnail.
+pub struct PictureGallery {
+    pub widget: Box,
+}
+
+impl PictureGallery {
+    pub fn new(mal_id: u32, urls: Vec<String>) -> Self {
+        let widget = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .build();
+
+        widget.append(&Label::builder()
+            .label(format!("Gallery ({})", urls.len()))
+            .xalign(0.0)
+            .css_classes(vec!["heading".to_string()])
+            .build());
+
+        let carousel = adw::Carousel::builder()
+            .spacing(12)
+            .allow_scroll_wheel(false)
+            .build();
+        let pages: Vec<ZoomablePicture> = urls
+            .iter()
+            .map(|url| ZoomablePicture::new(url, &carousel))
+            .collect();
+        for page in &pages {
+            carousel.append(&page.scrolled_window);
+        }
+        widget.append(&carousel);
+
+        widget.append(&adw::CarouselIndicatorDots::builder()
+            .carousel(&carousel)
+            .build());
+
+        let controls = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .build();
+        widget.append(&controls);
+
+        // The carousel holds the pages, so its handlers and the buttons only hold them weakly
+        let pages: Rc<Vec<WeakZoomablePicture>> = Rc::new(pages.iter().map(ZoomablePicture::downgrade).collect());
+        let current_page = {
+            let carousel = carousel.downgrade();
+            let pages = pages.clone();
+            move || {
+                let carousel = carousel.upgrade()?;
+                pages.get(carousel.position().round() as usize)?.upgrade()
+            }
+        };
+
+        for (icon_name, tooltip, factor) in [
+            ("zoom-out-symbolic", "Zoom out", Some(1.0 / ZOOM_STEP)),
+            ("zoom-original-symbolic", "Reset zoom", None),
+            ("zoom-in-symbolic", "Zoom in", Some(ZOOM_STEP)),
+        ] {
+            let button = Button::builder()
+                .icon_name(icon_name)
+                .tooltip_text(tooltip)
+                .css_classes(vec!["flat".to_string()])
+                .build();
+            let current_page = current_page.clone();
+            button.connect_clicked(move |_| {
+                if let Some(page) = current_page() {
+                    page.set_zoom(factor.map_or(1.0, |factor| page.zoom.get() * factor));
+                }
+            });
+            controls.append(&button);
+        }
+
+        // Leaving a page resets its zoom so swiping works again
+        carousel.connect_page_changed({
+            let pages = pages.clone();
+            move |_, index| {
+                for (position, page) in pages.iter().enumerate() {
+                    if position != index as usize
+                        && let Some(page) = page.upgrade()
+                    {
+                        page.set_zoom(1.0);
+                    }
+                }
+            }
+        });
+
+        let spacer = Box::builder()
+            .hexpand(true)
+            .build();
+        controls.append(&spacer);
+
+        let default_button = Button::builder()
+            .label("Use Default Picture")
+            .tooltip_text("Show Jikan's picture on this character's card again")
+            .build();
+        let thumbnail_button = Button::builder()
+            .label("Use as Card Thumbnail")
+            .build();
+        controls.append(&default_button);
+        controls.append(&thumbnail_button);
+
+        let thumbnail: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let update_buttons = {
+            let thumbnail = thumbnail.clone();
+            let thumbnail_button = thumbnail_button.downgrade();
+            let default_button = default_button.downgrade();
+            let carousel = carousel.downgrade();
+            let urls = urls.clone();
+            move || {
+                let (Some(thumbnail_button), Some(default_button), Some(carousel)) =
+                    (thumbnail_button.upgrade(), default_button.upgrade(), carousel.upgrade())
+                else {
+                    return;
+                };
+                let record = FavoritesStorage::new().get_favorite(mal_id).ok().flatten();
+                *thumbnail.borrow_mut() = record.as_ref().and_then(|record| record.thumbnail_url.clone());
+
+                // Only saved characters have a card of their own to customise
+                thumbnail_button.set_visible(record.is_some());
+                default_button.set_visible(thumbnail.borrow().is_some());
+                default_button.set_sensitive(true);
+
+                let shown = urls.get(carousel.position().round() as usize);
+                let is_thumbnail = shown.is_some() && shown == thumbnail.borrow().as_ref();
+                thumbnail_button.set_sensitive(!is_thumbnail);
+                thumbnail_button.set_label(if is_thumbnail { "Current Card Thumbnail" } else { "Use as Card Thumbnail" });
+            }
+        };
+        update_buttons();
+
+        carousel.connect_page_changed({
+            let update_buttons = update_buttons.clone();
+            move |_, _| update_buttons()
+        });
+
+        let model = FavoritesModel::shared();
+        let subscription = model.subscribe({
+            let widget = widget.downgrade();
+            let update_buttons = update_buttons.clone();
+            move |change| {
+                if change.affects(mal_id) && widget.upgrade().is_some() {
+                    update_buttons();
+                }
+            }
+        });
+        widget.connect_destroy(move |_| model.unsubscribe(subscription));
+
+        thumbnail_button.connect_clicked({
+            let carousel = carousel.downgrade();
+            move |button| {
+                let Some(carousel) = carousel.upgrade() else {
+                    return;
+                };
+                let url = urls.get(carousel.position().round() as usize).cloned();
+                if url.is_some() {
+                    Self::set_thumbnail(button, mal_id, url);
+                }
+            }
+        });
+        default_button.connect_clicked(move |button| Self::set_thumbnail(button, mal_id, None));
+
+        Self { widget }
+    }
+
+    fn set_thumbnail(button: &Button, mal_id: u32, thumbnail_url: Option<String>) {
+        let overlay = toast::overlay_for(button);
+        let button = button.clone();
+        button.set_sensitive(false);
+
+        glib::MainContext::default().spawn_local(async move {
+            let message = match FavoritesModel::shared().set_thumbnail(mal_id, thumbnail_url.clone()).await {
+                Ok(()) if thumbnail_url.is_some() => "Card thumbnail updated".to_string(),
+                Ok(()) => "Card shows the default picture again".to_string(),
+                Err(e) => {
+                    error!("Failed to set thumbnail for {}: {}", mal_id, e);
+                    button.set_sensitive(true);
+                    format!("Could not change the thumbnail: {}", e)
+                }
+            };
+            if let Some(overlay) = overlay {
+                overlay.add_toast(toast::plain_toast(&message));
+            }
+        });
+    }
+}
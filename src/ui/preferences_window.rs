@@ -0,0 +1,222 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::gio;
+use log::error;
+
+use crate::api::client_builder::DEFAULT_BASE_URL;
+use crate::api::search_query::MAX_LIMIT;
+use crate::ui::utils::app_settings::{AppSettings, CardSize, ColorScheme};
+use crate::utils::logger::{self, LOG_ENV};
+
+// Edits the GSettings-backed preferences; every change is saved and applied right away
+pub struct PreferencesWindow;
+
+impl PreferencesWindow {
+    pub fn present(parent: &adw::ApplicationWindow) {
+        let app_settings = AppSettings::shared();
+
+        let window = adw::PreferencesWindow::builder()
+            .title("Preferences")
+            .transient_for(parent)
+            .modal(true)
+            .search_enabled(false)
+            .build();
+        match app_settings.gsettings() {
+            Some(settings) => {
+                window.add(&Self::general_page(settings));
+                window.add(&Self::network_page(settings));
+            }
+            None => window.add(&Self::unavailable_page()),
+        }
+        window.present();
+    }
+
+    fn unavailable_page() -> adw::PreferencesPage {
+        let group = adw::PreferencesGroup::builder()
+            .title("Preferences Unavailable")
+            .description("The settings schema could not be loaded, so the defaults are in use. The Debug Log has details.")
+            .build();
+        let page = adw::PreferencesPage::new();
+        page.add(&group);
+        page
+    }
+
+    fn general_page(settings: &gio::Settings) -> adw::PreferencesPage {
+        let appearance = adw::PreferencesGroup::builder().title("Appearance").build();
+        let labels: Vec<&str> = ColorScheme::ALL.iter().map(|scheme| scheme.label()).collect();
+        appearance.add(&Self::choice_row(settings, "color-scheme", "Theme", None, &labels, |index| {
+            ColorScheme::ALL[index].nick()
+        }));
+        let labels: Vec<&str> = CardSize::ALL.iter().map(|size| size.label()).collect();
+        appearance.add(&Self::choice_row(
+            settings,
+            "card-size",
+            "Card Size",
+            Some("Used for cards loaded from now on"),
+            &labels,
+            |index| CardSize::ALL[index].nick(),
+        ));
+
+        let browsing = adw::PreferencesGroup::builder().title("Browsing").build();
+        browsing.add(&Self::spin_row(
+            settings,
+            "results-per-page",
+            "Results per Page",
+            None,
+            (1.0, MAX_LIMIT as f64, 1.0),
+        ));
+        let sfw_row = adw::SwitchRow::builder()
+            .title("Hide Adult Entries")
+            .subtitle("Filter adult anime and manga out of listings and searches")
+            .build();
+        settings.bind("sfw", &sfw_row, "active").build();
+        browsing.add(&sfw_row);
+
+        let diagnostics = adw::PreferencesGroup::builder().title("Diagnostics").build();
+        let verbose_row = adw::SwitchRow::builder()
+            .title("Verbose Logging")
+            .subtitle("Also log debug messages, shown in the Debug Log")
+            .build();
+        settings.bind("verbose-logging", &verbose_row, "active").build();
+        if logger::filter_from_env() {
+            verbose_row.set_sensitive(false);
+            verbose_row.set_subtitle(&format!("Set by {}", LOG_ENV));
+        }
+        diagnostics.add(&verbose_row);
+
+        let page = adw::PreferencesPage::builder()
+            .title("General")
+            .icon_name("preferences-system-symbolic")
+            .build();
+        page.add(&appearance);
+        page.add(&browsing);
+        page.add(&diagnostics);
+        page
+    }
+
+    fn network_page(settings: &gio::Settings) -> adw::PreferencesPage {
+        let jikan = adw::PreferencesGroup::builder()
+            .title("Jikan API")
+            .description("Environment variables such as WAIFU_VIEWER_JIKAN_URL take precedence")
+            .build();
+        jikan.add(&Self::base_url_row(settings));
+        jikan.add(&Self::spin_row(
+            settings,
+            "request-timeout-secs",
+            "Request Timeout",
+            Some("Seconds until a request is abandoned"),
+            (1.0, 300.0, 1.0),
+        ));
+        jikan.add(&Self::spin_row(
+            settings,
+            "connect-timeout-secs",
+            "Connect Timeout",
+            Some("Seconds to wait for a connection"),
+            (1.0, 120.0, 1.0),
+        ));
+
+        let cache = adw::PreferencesGroup::builder().title("Cache").build();
+        cache.add(&Self::spin_row(
+            settings,
+            "response-cache-mb",
+            "API Cache Limit",
+            Some("Megabytes of API responses kept on disk"),
+            (1.0, 2048.0, 10.0),
+        ));
+        cache.add(&Self::spin_row(
+            settings,
+            "image-cache-mb",
+            "Image Cache Limit",
+            Some("Megabytes of images kept on disk"),
+            (1.0, 4096.0, 10.0),
+        ));
+
+        let page = adw::PreferencesPage::builder()
+            .title("Network")
+            .icon_name("network-wired-symbolic")
+            .build();
+        page.add(&jikan);
+        page.add(&cache);
+        page
+    }
+
+    // A numeric row bound to an unsigned key; `range` is (lower, upper, step)
+    fn spin_row(
+        settings: &gio::Settings,
+        key: &str,
+        title: &str,
+        subtitle: Option<&str>,
+        (lower, upper, step): (f64, f64, f64),
+    ) -> adw::SpinRow {
+        let row = adw::SpinRow::with_range(lower, upper, step);
+        row.set_title(title);
+        if let Some(subtitle) = subtitle {
+            row.set_subtitle(subtitle);
+        }
+        settings.bind(key, &row, "value").build();
+        row
+    }
+
+    // A drop-down row for an enum key; `nick` maps a position in `labels` to the stored value
+    fn choice_row(
+        settings: &gio::Settings,
+        key: &'static str,
+        title: &str,
+        subtitle: Option<&str>,
+        labels: &[&str],
+        nick: fn(usize) -> &'static str,
+    ) -> adw::ComboRow {
+        let row = adw::ComboRow::builder()
+            .title(title)
+            .model(&gtk::StringList::new(labels))
+            .build();
+        if let Some(subtitle) = subtitle {
+            row.set_subtitle(subtitle);
+        }
+
+        let current = settings.string(key);
+        if let Some(index) = (0..labels.len()).find(|&index| nick(index) == current.as_str()) {
+            row.set_selected(index as u32);
+        }
+
+        let count = labels.len();
+        let settings = settings.clone();
+        row.connect_selected_notify(move |row| {
+            let index = row.selected() as usize;
+            if index < count
+                && let Err(e) = settings.set_string(key, nick(index))
+            {
+                error!("Failed to save {}: {}", key, e);
+            }
+        });
+        row
+    }
+
+    // Only saved when applied, and only if it parses as a URL
+    fn base_url_row(settings: &gio::Settings) -> adw::EntryRow {
+        let row = adw::EntryRow::builder()
+            .title(format!("Base URL (default {})", DEFAULT_BASE_URL))
+            .text(settings.string("jikan-base-url").as_str())
+            .show_apply_button(true)
+            .build();
+
+        let settings = settings.clone();
+        row.connect_apply(move |row| {
+            let text = row.text().trim().to_string();
+            if !text.is_empty() && reqwest::Url::parse(&text).is_err() {
+                row.add_css_class("error");
+                if let Some(window) = row.root().and_downcast::<adw::PreferencesWindow>() {
+                    window.add_toast(adw::Toast::new("The base URL is not a valid URL"));
+                }
+                return;
+            }
+
+            row.remove_css_class("error");
+            if let Err(e) = settings.set_string("jikan-base-url", &text) {
+                error!("Failed to save the Jikan base URL: {}", e);
+            }
+        });
+        row
+    }
+}
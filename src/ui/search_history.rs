@@ -0,0 +1,331 @@
+use libadwaita::gtk;
+use gtk::prelude::*;
+use gtk::{glib, Box, Button, Label, ListBoxRow, Orientation};
+use log::warn;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::api::search_query::{CharacterOrderBy, CharacterSearchQuery};
+use crate::models::saved_search::SavedSearch;
+use crate::storage::favorites::FavoritesStorage;
+use crate::ui::pages::explore_page::ExplorePage;
+use crate::ui::utils::toast;
+
+// Most suggestions listed under the search entry at once
+const MAX_SUGGESTIONS: usize = 8;
+
+// Recent searches offered as completions under the Explore search entry, and saved
+// searches shown as chips. Both are kept in the favorites file.
+#[derive(Clone)]
+pub struct SearchHistory {
+    explore_page: ExplorePage,
+    submit: Rc<dyn Fn()>,
+    history: Rc<RefCell<Vec<String>>>,
+    // The history entries currently listed in the popover, in row order
+    suggestions: Rc<RefCell<Vec<String>>>,
+    saved: Rc<RefCell<Vec<SavedSearch>>>,
+}
+
+impl SearchHistory {
+    // `submit` runs the search currently described by the Explore page
+    pub fn connect(explore_page: &ExplorePage, submit: impl Fn() + 'static) -> Self {
+        let storage = FavoritesStorage::new();
+        let history = storage.get_search_history().unwrap_or_else(|e| {
+            warn!("Failed to load search history: {}", e);
+            Vec::new()
+        });
+        let saved = storage.get_saved_searches().unwrap_or_else(|e| {
+            warn!("Failed to load saved searches: {}", e);
+            Vec::new()
+        });
+
+        let search_history = Self {
+            explore_page: explore_page.clone(),
+            submit: Rc::new(submit),
+            history: Rc::new(RefCell::new(history)),
+            suggestions: Rc::new(RefCell::new(Vec::new())),
+            saved: Rc::new(RefCell::new(saved)),
+        };
+
+        search_history.connect_suggestions();
+        search_history.connect_save_button();
+        search_history.render_saved_searches();
+        search_history
+    }
+
+    // Runs the current search and adds its text to the history
+    pub fn submit(&self) {
+        self.explore_page.history_popover.popdown();
+        (self.submit)();
+
+        let text = self.explore_page.search_entry.text().trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+
+        let this = self.clone();
+        glib::MainContext::default().spawn_local(async move {
+            match FavoritesStorage::new().record_search(&text).await {
+                Ok(history) => *this.history.borrow_mut() = history,
+                Err(e) => warn!("Failed to record search: {}", e),
+            }
+        });
+    }
+
+    fn connect_suggestions(&self) {
+        let entry = &self.explore_page.search_entry;
+
+        entry.connect_changed({
+            let this = self.clone();
+            move |_| this.show_suggestions()
+        });
+
+        let focus = gtk::EventControllerFocus::new();
+        focus.connect_enter({
+            let this = self.clone();
+            move |_| this.show_suggestions()
+        });
+        focus.connect_leave({
+            let popover = self.explore_page.history_popover.clone();
+            move |_| popover.popdown()
+        });
+        entry.add_controller(focus);
+
+        let keys = gtk::EventControllerKey::new();
+        keys.connect_key_pressed({
+            let popover = self.explore_page.history_popover.clone();
+            move |_, key, _, _| {
+                if key == gtk::gdk::Key::Escape && popover.is_visible() {
+                    popover.popdown();
+                    return glib::Propagation::Stop;
+                }
+                glib::Propagation::Proceed
+            }
+        });
+        entry.add_controller(keys);
+
+        self.explore_page.history_list.connect_row_activated({
+            let this = self.clone();
+            move |_, row| {
+                let Some(text) = this.suggestions.borrow().get(row.index() as usize).cloned() else {
+                    return;
+                };
+                this.explore_page.search_entry.set_text(&text);
+                this.explore_page.search_entry.set_position(-1);
+                this.submit();
+            }
+        });
+    }
+
+    // Lists past searches containing the entry text, or all of them when it is empty
+    fn show_suggestions(&self) {
+        let entry = &self.explore_page.search_entry;
+        let popover = &self.explore_page.history_popover;
+        let list = &self.explore_page.history_list;
+
+        let text = entry.text().trim().to_lowercase();
+        let suggestions: Vec<String> = self
+            .history
+            .borrow()
+            .iter()
+            .filter(|past| {
+                let past = past.to_lowercase();
+                past != text && past.contains(&text)
+            })
+            .take(MAX_SUGGESTIONS)
+            .cloned()
+            .collect();
+
+        while let Some(child) = list.first_child() {
+            list.remove(&child);
+        }
+        for suggestion in &suggestions {
+            list.append(&self.create_suggestion_row(suggestion));
+        }
+        *self.suggestions.borrow_mut() = suggestions;
+
+        // The entry's inner text widget holds the focus, not the entry itself
+        let focused = entry.state_flags().contains(gtk::StateFlags::FOCUS_WITHIN);
+        if self.suggestions.borrow().is_empty() || !focused {
+            popover.popdown();
+        } else {
+            popover.set_width_request(entry.width());
+            popover.popup();
+        }
+    }
+
+    fn create_suggestion_row(&self, text: &str) -> ListBoxRow {
+        let row_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(6)
+            .build();
+
+        row_box.append(&Label::builder()
+            .label(text)
+            .xalign(0.0)
+            .hexpand(true)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build());
+
+        let remove_button = Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Remove from history")
+            .css_classes(vec!["flat".to_string(), "circular".to_string()])
+            .focus_on_click(false)
+            .build();
+        remove_button.connect_clicked({
+            let this = self.clone();
+            let text = text.to_string();
+            move |_| this.remove_from_history(&text)
+        });
+        row_box.append(&remove_button);
+
+        ListBoxRow::builder()
+            .child(&row_box)
+            .focusable(false)
+            .build()
+    }
+
+    fn remove_from_history(&self, text: &str) {
+        let this = self.clone();
+        let text = text.to_string();
+        glib::MainContext::default().spawn_local(async move {
+            match FavoritesStorage::new().remove_search(&text).await {
+                Ok(history) => {
+                    *this.history.borrow_mut() = history;
+                    this.show_suggestions();
+                }
+                Err(e) => this.show_toast(&format!("Could not remove \"{}\" from the history: {}", text, e)),
+            }
+        });
+    }
+
+    fn connect_save_button(&self) {
+        let this = self.clone();
+        self.explore_page.save_search_button.connect_clicked(move |_| {
+            let query = this.explore_page.search_query();
+            if !query.has_criteria() {
+                this.show_toast("Enter a name or pick a starting letter to save a search");
+                return;
+            }
+
+            let name = this.unique_name(&Self::default_name(&query));
+            let this = this.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let storage = FavoritesStorage::new();
+                match storage.save_search(&name, query).await {
+                    Ok(()) => {
+                        this.reload_saved_searches(&storage);
+                        this.show_toast(&format!("Saved search \"{}\"", name));
+                    }
+                    Err(e) => this.show_toast(&e),
+                }
+            });
+        });
+    }
+
+    fn reload_saved_searches(&self, storage: &FavoritesStorage) {
+        match storage.get_saved_searches() {
+            Ok(saved) => {
+                *self.saved.borrow_mut() = saved;
+                self.render_saved_searches();
+            }
+            Err(e) => warn!("Failed to load saved searches: {}", e),
+        }
+    }
+
+    fn render_saved_searches(&self) {
+        let chips = &self.explore_page.saved_searches_box;
+        while let Some(child) = chips.first_child() {
+            chips.remove(&child);
+        }
+
+        let saved = self.saved.borrow();
+        for search in saved.iter() {
+            chips.insert(&self.create_chip(search), -1);
+        }
+        chips.set_visible(!saved.is_empty());
+    }
+
+    fn create_chip(&self, search: &SavedSearch) -> Box {
+        let chip = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .css_classes(vec!["linked".to_string()])
+            .build();
+
+        let run_button = Button::builder()
+            .label(search.name.as_str())
+            .tooltip_text("Run this saved search")
+            .build();
+        run_button.connect_clicked({
+            let this = self.clone();
+            let query = search.query.clone();
+            move |_| {
+                this.explore_page.apply_search_query(&query);
+                this.submit();
+            }
+        });
+
+        let remove_button = Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Remove saved search")
+            .build();
+        remove_button.connect_clicked({
+            let this = self.clone();
+            let name = search.name.clone();
+            move |_| {
+                let this = this.clone();
+                let name = name.clone();
+                glib::MainContext::default().spawn_local(async move {
+                    let storage = FavoritesStorage::new();
+                    match storage.remove_saved_search(&name).await {
+                        Ok(()) => this.reload_saved_searches(&storage),
+                        Err(e) => this.show_toast(&format!("Could not remove \"{}\": {}", name, e)),
+                    }
+                });
+            }
+        });
+
+        chip.append(&run_button);
+        chip.append(&remove_button);
+        chip
+    }
+
+    // Names a saved search after its text and the filters that narrow or order it
+    fn default_name(query: &CharacterSearchQuery) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(text) = query.text() {
+            parts.push(text.to_string());
+        }
+        if let Some(letter) = query.starting_letter() {
+            parts.push(format!("starts with {}", letter));
+        }
+        match query.ordering() {
+            Some(CharacterOrderBy::Favorites) => parts.push("by favorites".to_string()),
+            Some(CharacterOrderBy::Name) => parts.push("by name".to_string()),
+            Some(CharacterOrderBy::MalId) => parts.push("by MAL ID".to_string()),
+            None => {}
+        }
+        parts.join(" · ")
+    }
+
+    // Appends a counter when a saved search already uses `name`
+    fn unique_name(&self, name: &str) -> String {
+        let saved = self.saved.borrow();
+        let taken = |candidate: &str| saved.iter().any(|search| search.name.eq_ignore_ascii_case(candidate));
+
+        let mut candidate = name.to_string();
+        let mut counter = 2;
+        while taken(&candidate) {
+            candidate = format!("{} ({})", name, counter);
+            counter += 1;
+        }
+        candidate
+    }
+
+    fn show_toast(&self, title: &str) {
+        if let Some(overlay) = toast::overlay_for(&self.explore_page.container) {
+            overlay.add_toast(toast::plain_toast(title));
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use libadwaita::gtk::{self, prelude::*, Box, Image, Label, Orientation};
+
+use crate::models::anime::Anime;
+use crate::models::manga::Manga;
+use crate::ui::utils::app_settings::AppSettings;
+use crate::ui::utils::image_loader::{ImageLoadError, ImageLoader, ImageSize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+    Anime,
+    Manga,
+}
+
+impl SeriesKind {
+    pub const ALL: [SeriesKind; 2] = [SeriesKind::Anime, SeriesKind::Manga];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SeriesKind::Anime => "Anime",
+            SeriesKind::Manga => "Manga",
+        }
+    }
+
+    // Window action that opens the series page, taking the MAL id
+    pub fn action_name(self) -> &'static str {
+        match self {
+            SeriesKind::Anime => "win.show-anime",
+            SeriesKind::Manga => "win.show-manga",
+        }
+    }
+}
+
+// What a card shows about an anime or manga
+#[derive(Debug, Clone)]
+pub struct SeriesSummary {
+    pub kind: SeriesKind,
+    pub mal_id: u32,
+    pub title: String,
+    pub image_url: Option<String>,
+    // e.g. "TV · 24 episodes · ★ 8.62"
+    pub details: String,
+}
+
+impl SeriesSummary {
+    pub fn from_anime(anime: &Anime) -> Self {
+        let mut details: Vec<String> = anime.media_type.iter().cloned().collect();
+        if let Some(episodes) = anime.episodes {
+            details.push(Self::count(episodes, "episode", "episodes"));
+        }
+        if let Some(year) = anime.year {
+            details.push(year.to_string());
+        }
+        Self::new(SeriesKind::Anime, anime.mal_id, &anime.title, anime.images.jpg.image_url.clone(), details, anime.score)
+    }
+
+    pub fn from_manga(manga: &Manga) -> Self {
+        let mut details: Vec<String> = manga.media_type.iter().cloned().collect();
+        if let Some(volumes) = manga.volumes {
+            details.push(Self::count(volumes, "volume", "volumes"));
+        }
+        Self::new(SeriesKind::Manga, manga.mal_id, &manga.title, manga.images.jpg.image_url.clone(), details, manga.score)
+    }
+
+    fn new(kind: SeriesKind, mal_id: u32, title: &str, image_url: Option<String>, mut details: Vec<String>, score: Option<f64>) -> Self {
+        if let Some(score) = score {
+            details.push(format!("★ {:.2}", score));
+        }
+        Self {
+            kind,
+            mal_id,
+            title: title.to_string(),
+            image_url,
+            details: details.join(" · "),
+        }
+    }
+
+    fn count(count: u32, singular: &str, plural: &str) -> String {
+        format!("{} {}", count, if count == 1 { singular } else { plural })
+    }
+}
+
+pub struct SeriesCard {
+    pub widget: Box,
+}
+
+impl SeriesCard {
+    pub fn new(summary: &SeriesSummary) -> Self {
+        let (image_width, image_height) = AppSettings::shared().card_size().image_size();
+
+        let widget = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .margin_top(15)
+            .margin_bottom(15)
+            .margin_start(15)
+            .margin_end(15)
+            .width_request(image_width + 20)
+            .build();
+
+        let image = Image::builder()
+            .icon_name("image-missing")
+            .pixel_size(48)
+            .halign(gtk::Align::Center)
+            .build();
+        image.set_size_request(image_width, image_height);
+
+        if let Some(image_url) = &summary.image_url {
+            let size = ImageSize::Fit { width: image_width, height: image_height };
+            ImageLoader::shared().load_for(&image, image_url, size, |image, result| {
+                match result {
+                    Ok(texture) => image.set_paintable(Some(&texture)),
+                    Err(ImageLoadError::Network) => image.set_icon_name(Some("network-offline-symbolic")),
+                    Err(ImageLoadError::Decode) => image.set_icon_name(Some("image-missing")),
+                }
+            });
+        }
+
+        let title_label = Label::builder()
+            .label(&summary.title)
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .lines(2)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .width_request(image_width)
+            .max_width_chars(20)
+            .build();
+
+        let details_label = Label::builder()
+            .label(&summary.details)
+            .wrap(true)
+            .justify(gtk::Justification::Center)
+            .max_width_chars(24)
+            .css_classes(vec!["dim-label".to_string(), "caption".to_string()])
+            .build();
+
+        widget.append(&image);
+        widget.append(&title_label);
+        widget.append(&details_label);
+
+        // Clicking the card opens the series page with its cast
+        widget.set_cursor_from_name(Some("pointer"));
+        widget.set_tooltip_text(Some(&summary.title));
+        let action_name = summary.kind.action_name();
+        let mal_id = summary.mal_id;
+        let click_gesture = gtk::GestureClick::new();
+        click_gesture.connect_released(move |gesture, _, _, _| {
+            let _ = gesture
+                .widget()
+                .activate_action(action_name, Some(&mal_id.to_variant()));
+        });
+        widget.add_controller(click_gesture);
+
+        Self { widget }
+    }
+}
@@ -0,0 +1,218 @@
+use libadwaita::gtk;
+use gtk::prelude::*;
+use gtk::Label;
+use log::warn;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::api::jikan::{JikanError, JikanPagination};
+use crate::ui::handlers::SearchHandler;
+use crate::ui::pages::series_browser::SeriesBrowser;
+use crate::ui::series_card::{SeriesCard, SeriesKind, SeriesSummary};
+use crate::ui::utils::api_handler::ApiHandler;
+use crate::ui::utils::paged_listing::{self, Paging};
+
+#[derive(Clone, PartialEq)]
+enum SeriesSource {
+    Top,
+    Search(String),
+}
+
+// Paging state of the series listing, like the character browser's
+#[derive(Default)]
+struct SeriesState {
+    listing: Option<(SeriesKind, SeriesSource)>,
+    paging: Paging,
+}
+
+impl AsMut<Paging> for SeriesState {
+    fn as_mut(&mut self) -> &mut Paging {
+        &mut self.paging
+    }
+}
+
+type SharedSeriesState = Rc<RefCell<SeriesState>>;
+
+pub struct SeriesHandler;
+
+impl SeriesHandler {
+    pub fn connect(browser: &SeriesBrowser) {
+        let state: SharedSeriesState = Rc::new(RefCell::new(SeriesState::default()));
+
+        let submit_search = {
+            let browser = browser.clone();
+            let state = state.clone();
+            move || {
+                let text = browser.search_entry.text().trim().to_string();
+                if !text.is_empty() {
+                    Self::start(&browser, &state, SeriesSource::Search(text));
+                }
+            }
+        };
+
+        browser.search_button.connect_clicked({
+            let submit_search = submit_search.clone();
+            move |_| submit_search()
+        });
+        browser.search_entry.connect_activate({
+            let submit_search = submit_search.clone();
+            move |_| submit_search()
+        });
+
+        browser.top_button.connect_clicked({
+            let browser = browser.clone();
+            let state = state.clone();
+            move |_| Self::start(&browser, &state, SeriesSource::Top)
+        });
+
+        // Switching between anime and manga re-runs whatever is listed
+        browser.kind_dropdown.connect_selected_notify({
+            let browser = browser.clone();
+            let state = state.clone();
+            move |_| {
+                let source = state.borrow().listing.as_ref().map(|(_, source)| source.clone());
+                if let Some(source) = source {
+                    Self::start(&browser, &state, source);
+                }
+            }
+        });
+
+        Self::connect_infinite_scroll(browser, state);
+    }
+
+    fn connect_infinite_scroll(browser: &SeriesBrowser, state: SharedSeriesState) {
+        let on_scroll = {
+            let browser = browser.clone();
+            move |adjustment: &gtk::Adjustment| {
+                if paged_listing::near_bottom(adjustment) {
+                    Self::load_next_page(&browser, &state);
+                }
+            }
+        };
+        paged_listing::connect_scroll(&browser.container, on_scroll);
+    }
+
+    // Replaces the listing with page 1 of `source`, aborting whatever was loading
+    fn start(browser: &SeriesBrowser, state: &SharedSeriesState, source: SeriesSource) {
+        let kind = browser.kind();
+        let generation = {
+            let mut state = state.borrow_mut();
+            state.listing = Some((kind, source.clone()));
+            state.paging.restart()
+        };
+
+        let container = &browser.series_container;
+        while let Some(child) = container.first_child() {
+            container.remove(&child);
+        }
+        browser.load_more_spinner.set_visible(false);
+        browser.load_more_spinner.stop();
+        browser.loading_spinner.set_visible(true);
+        browser.loading_spinner.start();
+
+        let browser = browser.clone();
+        paged_listing::spawn_listing(state, {
+            let state = state.clone();
+            async move {
+                let result = Self::fetch_page(kind, &source, 1).await;
+                if !state.borrow().paging.is_current(generation) {
+                    return;
+                }
+
+                browser.loading_spinner.set_visible(false);
+                browser.loading_spinner.stop();
+
+                let container = &browser.series_container;
+                match result {
+                    Ok((series, _)) if series.is_empty() => {
+                        container.insert(&Label::new(Some("No series found")), -1);
+                    }
+                    Ok((series, has_next_page)) => {
+                        state.borrow_mut().paging.record_page(1, has_next_page);
+                        Self::add_series_cards(container, &series);
+                    }
+                    Err(e) => {
+                        warn!("Failed to load series: {}", e);
+                        SearchHandler::handle_error(container, &e, "Error loading series");
+                    }
+                }
+                state.borrow_mut().paging.loading = false;
+            }
+        });
+    }
+
+    fn load_next_page(browser: &SeriesBrowser, state: &SharedSeriesState) {
+        let (kind, source, page, generation) = {
+            let mut state = state.borrow_mut();
+            let Some((kind, source)) = state.listing.clone() else {
+                return;
+            };
+            let Some((page, generation)) = state.paging.begin_next_page() else {
+                return;
+            };
+            (kind, source, page, generation)
+        };
+
+        browser.load_more_spinner.set_visible(true);
+        browser.load_more_spinner.start();
+
+        let browser = browser.clone();
+        paged_listing::spawn_listing(state, {
+            let state = state.clone();
+            async move {
+                let result = Self::fetch_page(kind, &source, page).await;
+                if !state.borrow().paging.is_current(generation) {
+                    return;
+                }
+
+                browser.load_more_spinner.set_visible(false);
+                browser.load_more_spinner.stop();
+
+                let container = &browser.series_container;
+                match result {
+                    Ok((series, has_next_page)) => {
+                        state.borrow_mut().paging.record_page(page, has_next_page);
+                        Self::add_series_cards(container, &series);
+                    }
+                    Err(e) => {
+                        state.borrow_mut().paging.has_next_page = false;
+                        SearchHandler::handle_error(container, &e, "Error loading more series");
+                    }
+                }
+                state.borrow_mut().paging.loading = false;
+            }
+        });
+    }
+
+    async fn fetch_page(kind: SeriesKind, source: &SeriesSource, page: u32) -> Result<(Vec<SeriesSummary>, bool), JikanError> {
+        let api_handler = ApiHandler::new();
+        let has_next = |pagination: &Option<JikanPagination>| {
+            pagination.as_ref().is_some_and(|pagination| pagination.has_next_page)
+        };
+
+        match kind {
+            SeriesKind::Anime => {
+                let response = match source {
+                    SeriesSource::Top => api_handler.get_top_anime(page).await?,
+                    SeriesSource::Search(text) => api_handler.search_anime(text, page).await?,
+                };
+                let series = response.data.iter().map(SeriesSummary::from_anime).collect();
+                Ok((series, has_next(&response.pagination)))
+            }
+            SeriesKind::Manga => {
+                let response = match source {
+                    SeriesSource::Top => api_handler.get_top_manga(page).await?,
+                    SeriesSource::Search(text) => api_handler.search_manga(text, page).await?,
+                };
+                let series = response.data.iter().map(SeriesSummary::from_manga).collect();
+                Ok((series, has_next(&response.pagination)))
+            }
+        }
+    }
+
+    fn add_series_cards(container: &gtk::FlowBox, series: &[SeriesSummary]) {
+        for summary in series {
+            container.insert(&SeriesCard::new(summary).widget, -1);
+        }
+    }
+}
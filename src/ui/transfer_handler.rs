@@ -0,0 +1,135 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+use gtk::{gio, glib};
+use log::error;
+
+use crate::storage::favorites::{FavoritesStorage, ImportReport};
+use crate::storage::transfer::{ImportMode, TransferFormat};
+use crate::ui::favorites_model::FavoritesModel;
+use crate::ui::utils::toast;
+
+pub struct TransferHandler;
+
+impl TransferHandler {
+    pub fn export(window: &adw::ApplicationWindow) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Favorites")
+            .modal(true)
+            .initial_name(format!("favorites.{}", TransferFormat::Json.extension()))
+            .filters(&Self::create_filters())
+            .build();
+
+        let window = window.clone();
+        glib::MainContext::default().spawn_local(async move {
+            // Dismissing the dialog also ends up here
+            let Ok(file) = dialog.save_future(Some(&window)).await else {
+                return;
+            };
+            let Some(path) = file.path() else {
+                Self::show_toast(&window, "Favorites can only be exported to a local file");
+                return;
+            };
+
+            let format = TransferFormat::from_path(&path);
+            let message = match FavoritesStorage::new().export(&path, format).await {
+                Ok(count) => format!("Exported {} favorites", count),
+                Err(e) => {
+                    error!("Failed to export favorites: {}", e);
+                    format!("Could not export favorites: {}", e)
+                }
+            };
+            Self::show_toast(&window, &message);
+        });
+    }
+
+    pub fn import(window: &adw::ApplicationWindow) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Import Favorites")
+            .modal(true)
+            .filters(&Self::create_filters())
+            .build();
+
+        let window = window.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let Ok(file) = dialog.open_future(Some(&window)).await else {
+                return;
+            };
+            let Some(path) = file.path() else {
+                Self::show_toast(&window, "Favorites can only be imported from a local file");
+                return;
+            };
+
+            let Some(mode) = Self::choose_mode(&window).await else {
+                return;
+            };
+
+            Self::show_toast(&window, "Importing favorites...");
+            let format = TransferFormat::from_path(&path);
+            let message = match FavoritesStorage::new().import(&path, format, mode).await {
+                Ok(report) => Self::describe_import(&report),
+                Err(e) => {
+                    error!("Failed to import favorites: {}", e);
+                    format!("Could not import favorites: {}", e)
+                }
+            };
+
+            FavoritesModel::shared().reload();
+            Self::show_toast(&window, &message);
+        });
+    }
+
+    // Asks whether the import should be merged into the collection or replace it
+    async fn choose_mode(window: &adw::ApplicationWindow) -> Option<ImportMode> {
+        let dialog = adw::MessageDialog::builder()
+            .heading("Import Favorites")
+            .body("Merging adds the characters you have not saved yet. Replacing discards your current favorites, lists and tags.")
+            .transient_for(window)
+            .modal(true)
+            .build();
+        dialog.add_response("cancel", "Cancel");
+        dialog.add_response("replace", "Replace");
+        dialog.add_response("merge", "Merge");
+        dialog.set_response_appearance("replace", adw::ResponseAppearance::Destructive);
+        dialog.set_response_appearance("merge", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("merge"));
+        dialog.set_close_response("cancel");
+
+        match dialog.choose_future().await.as_str() {
+            "merge" => Some(ImportMode::Merge),
+            "replace" => Some(ImportMode::Replace),
+            _ => None,
+        }
+    }
+
+    fn create_filters() -> gio::ListStore {
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        for format in TransferFormat::ALL {
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some(format.name()));
+            filter.add_suffix(format.extension());
+            filters.append(&filter);
+        }
+        filters
+    }
+
+    fn describe_import(report: &ImportReport) -> String {
+        let mut summary = format!("Imported {} favorites", report.added);
+        if report.skipped > 0 {
+            summary.push_str(&format!(", {} already saved", report.skipped));
+        }
+        if report.failed > 0 {
+            summary.push_str(&format!(", {} could not be fetched", report.failed));
+        }
+        if report.failed_images > 0 {
+            summary.push_str(&format!(", {} portraits failed to download", report.failed_images));
+        }
+        summary
+    }
+
+    fn show_toast(window: &adw::ApplicationWindow, title: &str) {
+        if let Some(overlay) = window.content().and_downcast::<adw::ToastOverlay>() {
+            overlay.add_toast(toast::plain_toast(title));
+        }
+    }
+}
@@ -1,5 +1,11 @@
-use crate::api::jikan::{JikanClient, JikanError};
-use crate::models::character::Character;
+use std::collections::HashSet;
+
+use crate::api::jikan::{JikanClient, JikanError, JikanResponse};
+use crate::api::search_query::CharacterSearchQuery;
+use crate::models::anime::Anime;
+use crate::models::character::{CastMember, Character, CharacterFull, CharacterPicture, CharacterVoice};
+use crate::models::manga::Manga;
+use crate::models::person::PersonFull;
 
 pub struct ApiHandler {
     jikan_client: JikanClient,
@@ -12,13 +18,78 @@ impl ApiHandler {
         }
     }
 
-    pub async fn get_top_characters(&self) -> Result<Vec<Character>, JikanError> {
-        self.jikan_client.get_top_characters().await
+    // Wraps an already configured client, e.g. one pointed at a mirror
+    pub fn with_client(jikan_client: JikanClient) -> Self {
+        Self { jikan_client }
+    }
+
+    pub async fn get_top_characters(&self, page: u32) -> Result<JikanResponse, JikanError> {
+        self.jikan_client.get_top_characters(page).await
     }
 
-    pub async fn search_characters(&self, query: &str) -> Result<Vec<Character>, JikanError> {
+    pub async fn search_characters(&self, query: &CharacterSearchQuery) -> Result<JikanResponse, JikanError> {
         self.jikan_client.search_characters(query).await
     }
 
-    
+    pub async fn get_random_characters(&self, count: usize, exclude: &HashSet<u32>) -> Result<Vec<Character>, JikanError> {
+        self.jikan_client.get_random_characters(count, exclude).await
+    }
+
+    pub async fn get_character(&self, mal_id: u32) -> Result<Character, JikanError> {
+        self.jikan_client.get_character(mal_id).await
+    }
+
+    pub async fn get_character_full(&self, mal_id: u32) -> Result<CharacterFull, JikanError> {
+        self.jikan_client.get_character_full(mal_id).await
+    }
+
+    pub async fn get_character_pictures(&self, mal_id: u32) -> Result<Vec<CharacterPicture>, JikanError> {
+        self.jikan_client.get_character_pictures(mal_id).await
+    }
+
+    pub async fn get_character_voices(&self, mal_id: u32) -> Result<Vec<CharacterVoice>, JikanError> {
+        self.jikan_client.get_character_voices(mal_id).await
+    }
+
+    pub async fn get_person_full(&self, mal_id: u32) -> Result<PersonFull, JikanError> {
+        self.jikan_client.get_person_full(mal_id).await
+    }
+
+    pub async fn get_top_anime(&self, page: u32) -> Result<JikanResponse<Anime>, JikanError> {
+        self.jikan_client.get_top_anime(page).await
+    }
+
+    pub async fn search_anime(&self, text: &str, page: u32) -> Result<JikanResponse<Anime>, JikanError> {
+        self.jikan_client.search_anime(text, page).await
+    }
+
+    pub async fn get_anime(&self, mal_id: u32) -> Result<Anime, JikanError> {
+        self.jikan_client.get_anime(mal_id).await
+    }
+
+    pub async fn get_anime_characters(&self, mal_id: u32) -> Result<Vec<CastMember>, JikanError> {
+        self.jikan_client.get_anime_characters(mal_id).await
+    }
+
+    pub async fn get_top_manga(&self, page: u32) -> Result<JikanResponse<Manga>, JikanError> {
+        self.jikan_client.get_top_manga(page).await
+    }
+
+    pub async fn search_manga(&self, text: &str, page: u32) -> Result<JikanResponse<Manga>, JikanError> {
+        self.jikan_client.search_manga(text, page).await
+    }
+
+    pub async fn get_manga(&self, mal_id: u32) -> Result<Manga, JikanError> {
+        self.jikan_client.get_manga(mal_id).await
+    }
+
+    pub async fn get_manga_characters(&self, mal_id: u32) -> Result<Vec<CastMember>, JikanError> {
+        self.jikan_client.get_manga_characters(mal_id).await
+    }
+}
+
+impl Default for ApiHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,315 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk::gio;
+use log::{error, warn};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::api::client_builder::JikanSettings;
+use crate::api::search_query::{CharacterSearchQuery, MAX_LIMIT};
+use crate::ui::utils::image_loader::ImageLoader;
+use crate::utils::logger;
+
+pub const APP_ID: &str = "com.example.WaifuViewer";
+// Compiled schema shipped inside the gresource bundle
+const BUNDLED_SCHEMAS: &str = "/com/example/WaifuViewer/schemas/gschemas.compiled";
+const MIB: u64 = 1024 * 1024;
+// Keys written by the window itself rather than the Preferences window
+const SESSION_KEYS: [&str; 6] = [
+    "window-width",
+    "window-height",
+    "window-maximized",
+    "last-page",
+    "last-search",
+    "scroll-position",
+];
+
+thread_local! {
+    static SHARED_SETTINGS: AppSettings = AppSettings::new();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl CardSize {
+    pub const ALL: [CardSize; 3] = [CardSize::Small, CardSize::Medium, CardSize::Large];
+
+    // Unknown nicks fall back to the default size
+    pub fn from_nick(nick: &str) -> Self {
+        match nick {
+            "small" => CardSize::Small,
+            "large" => CardSize::Large,
+            _ => CardSize::Medium,
+        }
+    }
+
+    pub fn nick(self) -> &'static str {
+        match self {
+            CardSize::Small => "small",
+            CardSize::Medium => "medium",
+            CardSize::Large => "large",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CardSize::Small => "Small",
+            CardSize::Medium => "Medium",
+            CardSize::Large => "Large",
+        }
+    }
+
+    // Width and height of a card's picture
+    pub fn image_size(self) -> (i32, i32) {
+        match self {
+            CardSize::Small => (120, 180),
+            CardSize::Medium => (180, 270),
+            CardSize::Large => (240, 360),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorScheme {
+    System,
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    pub const ALL: [ColorScheme; 3] = [ColorScheme::System, ColorScheme::Light, ColorScheme::Dark];
+
+    pub fn from_nick(nick: &str) -> Self {
+        match nick {
+            "light" => ColorScheme::Light,
+            "dark" => ColorScheme::Dark,
+            _ => ColorScheme::System,
+        }
+    }
+
+    pub fn nick(self) -> &'static str {
+        match self {
+            ColorScheme::System => "system",
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorScheme::System => "Follow System",
+            ColorScheme::Light => "Light",
+            ColorScheme::Dark => "Dark",
+        }
+    }
+
+    fn adw(self) -> adw::ColorScheme {
+        match self {
+            ColorScheme::System => adw::ColorScheme::Default,
+            ColorScheme::Light => adw::ColorScheme::ForceLight,
+            ColorScheme::Dark => adw::ColorScheme::ForceDark,
+        }
+    }
+}
+
+// How the main window looked when it was last closed
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowState {
+    // Unmaximized size
+    pub width: i32,
+    pub height: i32,
+    pub maximized: bool,
+    // Name of the visible main view
+    pub page: String,
+    // Vertical scroll offset of the Explore grid
+    pub scroll: f64,
+}
+
+// The app's preferences and session, stored through GSettings. Without a usable schema
+// every key reads as its default and writes are dropped.
+#[derive(Clone)]
+pub struct AppSettings {
+    settings: Option<gio::Settings>,
+}
+
+impl AppSettings {
+    fn new() -> Self {
+        let Some(schema) = Self::lookup_schema() else {
+            error!("No settings schema could be loaded; using the defaults without saving changes");
+            return Self { settings: None };
+        };
+        let settings = gio::Settings::new_full(&schema, None::<&gio::SettingsBackend>, None);
+
+        // Keep the API client, image cache and theme in step with the preferences
+        settings.connect_changed(None, |settings, key| {
+            if !SESSION_KEYS.contains(&key) {
+                Self { settings: Some(settings.clone()) }.apply();
+            }
+        });
+
+        Self { settings: Some(settings) }
+    }
+
+    // The settings shared by the whole app on the main thread
+    pub fn shared() -> Self {
+        SHARED_SETTINGS.with(|settings| settings.clone())
+    }
+
+    // The underlying GSettings, for binding widgets to keys
+    pub fn gsettings(&self) -> Option<&gio::Settings> {
+        self.settings.as_ref()
+    }
+
+    // Pushes the preferences to the parts of the app that read them outside the UI
+    pub fn apply(&self) {
+        JikanSettings::install(self.jikan_settings());
+        ImageLoader::shared().set_disk_cache_limit(self.uint("image-cache-mb", 200) as u64 * MIB);
+        adw::StyleManager::default().set_color_scheme(self.color_scheme().adw());
+        logger::set_verbose(self.boolean("verbose-logging", false));
+    }
+
+    pub fn results_per_page(&self) -> u32 {
+        self.uint("results-per-page", MAX_LIMIT)
+    }
+
+    pub fn card_size(&self) -> CardSize {
+        CardSize::from_nick(&self.string("card-size"))
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        ColorScheme::from_nick(&self.string("color-scheme"))
+    }
+
+    pub fn window_state(&self) -> WindowState {
+        let Some(settings) = &self.settings else {
+            return WindowState {
+                width: 1000,
+                height: 800,
+                maximized: false,
+                page: "explore".to_string(),
+                scroll: 0.0,
+            };
+        };
+        WindowState {
+            width: settings.int("window-width"),
+            height: settings.int("window-height"),
+            maximized: settings.boolean("window-maximized"),
+            page: settings.string("last-page").to_string(),
+            scroll: settings.double("scroll-position"),
+        }
+    }
+
+    pub fn save_window_state(&self, state: &WindowState) -> Result<(), String> {
+        let Some(settings) = &self.settings else {
+            return Ok(());
+        };
+        settings
+            .set_int("window-width", state.width)
+            .and_then(|_| settings.set_int("window-height", state.height))
+            .and_then(|_| settings.set_boolean("window-maximized", state.maximized))
+            .and_then(|_| settings.set_string("last-page", &state.page))
+            .and_then(|_| settings.set_double("scroll-position", state.scroll))
+            .map_err(|e| e.to_string())?;
+        // The process may exit right after the window closes
+        gio::Settings::sync();
+        Ok(())
+    }
+
+    // The search to re-run on launch, if the last listing was one
+    pub fn last_search(&self) -> Option<CharacterSearchQuery> {
+        decode_search(&self.string("last-search"))
+    }
+
+    pub fn set_last_search(&self, query: Option<&CharacterSearchQuery>) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        if let Err(e) = settings.set_string("last-search", &encode_search(query)) {
+            error!("Failed to save the last search: {}", e);
+        }
+    }
+
+    // How API clients should be configured; an empty base URL keeps the default
+    pub fn jikan_settings(&self) -> JikanSettings {
+        let base_url = self.string("jikan-base-url").trim().to_string();
+        JikanSettings {
+            base_url: Some(base_url).filter(|url| !url.is_empty()),
+            timeout_secs: Some(self.uint("request-timeout-secs", 30) as u64),
+            connect_timeout_secs: Some(self.uint("connect-timeout-secs", 10) as u64),
+            page_size: Some(self.results_per_page()),
+            sfw: Some(self.boolean("sfw", true)),
+            cache_max_bytes: Some(self.uint("response-cache-mb", 50) as u64 * MIB),
+            ..JikanSettings::default()
+        }
+    }
+
+    // `default` mirrors the schema, for when there is none
+    fn uint(&self, key: &str, default: u32) -> u32 {
+        self.settings.as_ref().map_or(default, |settings| settings.uint(key))
+    }
+
+    fn boolean(&self, key: &str, default: bool) -> bool {
+        self.settings.as_ref().map_or(default, |settings| settings.boolean(key))
+    }
+
+    // Enum and string keys; unknown values already fall back to their defaults
+    fn string(&self, key: &str) -> String {
+        self.settings
+            .as_ref()
+            .map(|settings| settings.string(key).to_string())
+            .unwrap_or_default()
+    }
+
+    // The bundled schema always matches this build; an installed one is only a fallback
+    fn lookup_schema() -> Option<gio::SettingsSchema> {
+        let default_source = gio::SettingsSchemaSource::default();
+        let bundled = Self::extract_bundled_schemas().and_then(|dir| {
+            gio::SettingsSchemaSource::from_directory(&dir, default_source.as_ref(), false)
+                .map_err(|e| error!("Failed to load the bundled settings schema: {}", e))
+                .ok()
+        });
+
+        bundled
+            .and_then(|source| source.lookup(APP_ID, false))
+            .or_else(|| default_source.and_then(|source| source.lookup(APP_ID, true)))
+    }
+
+    // GLib only reads compiled schemas from a directory, so the bundled file is copied out,
+    // to the cache directory or else the temporary one
+    fn extract_bundled_schemas() -> Option<PathBuf> {
+        let data = gio::resources_lookup_data(BUNDLED_SCHEMAS, gio::ResourceLookupFlags::NONE)
+            .map_err(|e| warn!("No bundled settings schema: {}", e))
+            .ok()?;
+
+        let cache_dir = dirs::cache_dir().map(|dir| dir.join("waifu-viewer").join("schemas"));
+        let temp_dir = std::env::temp_dir().join("waifu-viewer-schemas");
+        cache_dir.into_iter().chain([temp_dir]).find(|dir| {
+            let written = fs::create_dir_all(dir).and_then(|_| fs::write(dir.join("gschemas.compiled"), &data));
+            if let Err(e) = &written {
+                warn!("Failed to write the settings schema to {}: {}", dir.display(), e);
+            }
+            written.is_ok()
+        })
+    }
+}
+
+// Stored form of the last search; `None` is the empty string
+pub fn encode_search(query: Option<&CharacterSearchQuery>) -> String {
+    query
+        .and_then(|query| serde_json::to_string(query).ok())
+        .unwrap_or_default()
+}
+
+// Anything unreadable, e.g. written by a newer version, counts as no search
+pub fn decode_search(stored: &str) -> Option<CharacterSearchQuery> {
+    if stored.is_empty() {
+        return None;
+    }
+    serde_json::from_str(stored)
+        .map_err(|e| warn!("Ignoring the saved search: {}", e))
+        .ok()
+}
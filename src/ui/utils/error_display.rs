@@ -2,6 +2,9 @@ use libadwaita::gtk;
 use libadwaita::prelude::*;
 use gtk::{Box, Orientation, Label, Image};
 
+use crate::api::jikan::JikanError;
+use crate::ui::handlers::{ErrorNotice, SearchHandler};
+
 pub fn create_error_display(icon_name: &str, title: &str, message: &str) -> Box {
     let error_box = Box::builder()
         .orientation(Orientation::Vertical)
@@ -32,4 +35,22 @@ pub fn create_error_display(icon_name: &str, title: &str, message: &str) -> Box
     error_box.append(&message_label);
     
     error_box
-}
\ No newline at end of file
+}
+
+// Shows a failed request the same way everywhere: offline and rate-limit notices, or else
+// `title` over the error itself
+pub fn for_jikan_error(error: &JikanError, title: &str) -> Box {
+    match SearchHandler::classify_error(error, title) {
+        ErrorNotice::Offline => create_error_display(
+            "network-offline-symbolic",
+            "No Internet Connection",
+            "Please check your internet connection and try again."
+        ),
+        ErrorNotice::RateLimited => create_error_display(
+            "alarm-symbolic",
+            "Too Many Requests",
+            "Jikan is rate limiting us. Please wait a moment and try again."
+        ),
+        ErrorNotice::Message(_) => create_error_display("dialog-error-symbolic", title, &error.to_string()),
+    }
+}
@@ -0,0 +1,265 @@
+use libadwaita::gtk;
+use gtk::prelude::*;
+use gtk::{gdk, gdk_pixbuf, glib};
+use log::warn;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::api::image_cache::ImageCache;
+
+// Maximum number of image downloads in flight at once
+const MAX_CONCURRENT_DOWNLOADS: usize = 6;
+// Number of decoded textures kept in memory
+const TEXTURE_CACHE_CAPACITY: usize = 300;
+
+thread_local! {
+    static SHARED_LOADER: ImageLoader = ImageLoader::new();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSize {
+    Original,
+    // Scaled down to fit inside the box while keeping the aspect ratio
+    Fit { width: i32, height: i32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageLoadError {
+    Network,
+    Decode,
+}
+
+struct TextureLru {
+    capacity: usize,
+    textures: HashMap<String, gdk::Texture>,
+    // Least recently used key at the front
+    order: VecDeque<String>,
+}
+
+impl TextureLru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            textures: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<gdk::Texture> {
+        let texture = self.textures.get(key)?.clone();
+        self.promote(key);
+        Some(texture)
+    }
+
+    fn insert(&mut self, key: String, texture: gdk::Texture) {
+        if self.textures.insert(key.clone(), texture).is_some() {
+            self.promote(&key);
+            return;
+        }
+
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.textures.remove(&oldest);
+            }
+        }
+    }
+
+    fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.textures.retain(|key, _| keep(key));
+        self.order.retain(|key| keep(key));
+    }
+
+    fn promote(&mut self, key: &str) {
+        if let Some(position) = self.order.iter().position(|k| k == key)
+            && let Some(key) = self.order.remove(position)
+        {
+            self.order.push_back(key);
+        }
+    }
+}
+
+// Aborts the wrapped tokio task when the future awaiting it is dropped
+struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+struct Inner {
+    client: reqwest::Client,
+    runtime: tokio::runtime::Handle,
+    downloads: Arc<Semaphore>,
+    disk_cache: RefCell<Option<ImageCache>>,
+    textures: RefCell<TextureLru>,
+}
+
+// Loads remote images for widgets: downloads run on the tokio runtime with bounded
+// concurrency, original bytes are kept on disk and decoded textures in memory
+#[derive(Clone)]
+pub struct ImageLoader {
+    inner: Rc<Inner>,
+}
+
+impl ImageLoader {
+    fn new() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                client: reqwest::Client::new(),
+                runtime: tokio::runtime::Handle::current(),
+                downloads: Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS)),
+                disk_cache: RefCell::new(ImageCache::new()),
+                textures: RefCell::new(TextureLru::new(TEXTURE_CACHE_CAPACITY)),
+            }),
+        }
+    }
+
+    // The loader shared by every widget on the main thread
+    pub fn shared() -> Self {
+        SHARED_LOADER.with(|loader| loader.clone())
+    }
+
+    // Loads `url` (http(s) or `file://`) and passes the result to `apply`. The request is cancelled if `owner`
+    // is destroyed before the image arrives, and `apply` is then never called.
+    pub fn load_for<W, F>(&self, owner: &W, url: &str, size: ImageSize, apply: F)
+    where
+        W: IsA<gtk::Widget>,
+        F: FnOnce(&W, Result<gdk::Texture, ImageLoadError>) + 'static,
+    {
+        if let Some(texture) = self.cached_texture(url, size) {
+            apply(owner, Ok(texture));
+            return;
+        }
+
+        let loader = self.clone();
+        let url = url.to_string();
+        let weak_owner = owner.downgrade();
+        let handle = glib::MainContext::default().spawn_local(async move {
+            let result = loader.load(&url, size).await;
+            if let Some(owner) = weak_owner.upgrade() {
+                apply(&owner, result);
+            }
+        });
+
+        owner.upcast_ref::<gtk::Widget>().connect_destroy(move |_| {
+            handle.abort();
+        });
+    }
+
+    pub async fn load(&self, url: &str, size: ImageSize) -> Result<gdk::Texture, ImageLoadError> {
+        if let Some(texture) = self.cached_texture(url, size) {
+            return Ok(texture);
+        }
+
+        let bytes = self.fetch_bytes(url).await?;
+        let pixbuf = gdk_pixbuf::Pixbuf::from_read(Cursor::new(bytes))
+            .map_err(|_| ImageLoadError::Decode)?;
+        let pixbuf = Self::scale(pixbuf, size);
+
+        let texture = gdk::Texture::for_pixbuf(&pixbuf);
+        self.inner
+            .textures
+            .borrow_mut()
+            .insert(Self::texture_key(url, size), texture.clone());
+        Ok(texture)
+    }
+
+    // Size limit for downloaded images kept on disk
+    pub fn set_disk_cache_limit(&self, max_bytes: u64) {
+        if let Some(cache) = self.inner.disk_cache.borrow_mut().as_mut() {
+            cache.set_max_bytes(max_bytes);
+        }
+    }
+
+    // Drops decoded textures, e.g. after stored images were replaced on disk
+    pub fn clear_memory_cache(&self) {
+        let mut textures = self.inner.textures.borrow_mut();
+        textures.textures.clear();
+        textures.order.clear();
+    }
+
+    // Drops the decoded textures of one image at every size
+    pub fn forget(&self, url: &str) {
+        let sized_prefix = format!("{}@", url);
+        self.inner
+            .textures
+            .borrow_mut()
+            .retain(|key| key != url && !key.starts_with(&sized_prefix));
+    }
+
+    fn cached_texture(&self, url: &str, size: ImageSize) -> Option<gdk::Texture> {
+        self.inner.textures.borrow_mut().get(&Self::texture_key(url, size))
+    }
+
+    async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>, ImageLoadError> {
+        let client = self.inner.client.clone();
+        let downloads = self.inner.downloads.clone();
+        let disk_cache = self.inner.disk_cache.borrow().clone();
+        let url = url.to_string();
+
+        let mut task = AbortOnDrop(self.inner.runtime.spawn(async move {
+            // Locally stored images (e.g. offline favorites) bypass the cache and download pool
+            if let Some(path) = url.strip_prefix("file://") {
+                return tokio::fs::read(path).await.map_err(|_| ImageLoadError::Network);
+            }
+
+            if let Some(bytes) = disk_cache.as_ref().and_then(|cache| cache.load(&url)) {
+                return Ok(bytes);
+            }
+
+            let _permit = downloads.acquire_owned().await.map_err(|_| ImageLoadError::Network)?;
+            let response = client
+                .get(&url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|_| ImageLoadError::Network)?;
+            let bytes = response.bytes().await.map_err(|_| ImageLoadError::Network)?.to_vec();
+
+            if let Some(cache) = &disk_cache
+                && let Err(e) = cache.store(&url, bytes.clone()).await
+            {
+                warn!("Failed to cache image {}: {}", url, e);
+            }
+
+            Ok(bytes)
+        }));
+
+        (&mut task.0).await.map_err(|_| ImageLoadError::Network)?
+    }
+
+    fn scale(pixbuf: gdk_pixbuf::Pixbuf, size: ImageSize) -> gdk_pixbuf::Pixbuf {
+        let ImageSize::Fit { width: max_width, height: max_height } = size else {
+            return pixbuf;
+        };
+
+        let current_width = pixbuf.width();
+        let current_height = pixbuf.height();
+
+        // Calculate new dimensions maintaining aspect ratio
+        let scale_factor = f64::min(
+            max_width as f64 / current_width as f64,
+            max_height as f64 / current_height as f64
+        );
+
+        let new_width = (current_width as f64 * scale_factor) as i32;
+        let new_height = (current_height as f64 * scale_factor) as i32;
+
+        pixbuf
+            .scale_simple(new_width.max(1), new_height.max(1), gdk_pixbuf::InterpType::Bilinear)
+            .unwrap_or(pixbuf)
+    }
+
+    fn texture_key(url: &str, size: ImageSize) -> String {
+        match size {
+            ImageSize::Original => url.to_string(),
+            ImageSize::Fit { width, height } => format!("{}@{}x{}", url, width, height),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use libadwaita::gtk;
+use gtk::{Align, Label};
+
+// A wrapping, start-aligned label as used in the detail pages' headers and bodies
+pub fn text_label(text: &str, css_classes: &[&str]) -> Label {
+    Label::builder()
+        .label(text)
+        .wrap(true)
+        .xalign(0.0)
+        .halign(Align::Start)
+        .css_classes(css_classes.iter().map(|class| class.to_string()).collect::<Vec<_>>())
+        .build()
+}
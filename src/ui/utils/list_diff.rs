@@ -0,0 +1,55 @@
+// One `ListStore::splice` call: remove `removed` items at `position`, then insert `added` there
+#[derive(Debug, Clone, PartialEq)]
+pub struct Splice<T> {
+    pub position: u32,
+    pub removed: u32,
+    pub added: Vec<T>,
+}
+
+// The splices that turn `current` into `wanted`, matching items by `key`. Items that are
+// unchanged and already in place are left alone, so their widgets are not rebuilt.
+pub fn splices<T, K>(current: &[T], wanted: &[T], key: impl Fn(&T) -> K) -> Vec<Splice<T>>
+where
+    T: Clone + PartialEq,
+    K: PartialEq,
+{
+    let mut items = current.to_vec();
+    let mut splices = Vec::new();
+    let mut apply = |items: &mut Vec<T>, splice: Splice<T>| {
+        let position = splice.position as usize;
+        items.splice(position..position + splice.removed as usize, splice.added.iter().cloned());
+        splices.push(splice);
+    };
+
+    // Drop what is gone first, so the rest only moves if its order really changed
+    let mut position = 0;
+    while position < items.len() {
+        if wanted.iter().any(|item| key(item) == key(&items[position])) {
+            position += 1;
+        } else {
+            apply(&mut items, Splice { position: position as u32, removed: 1, added: Vec::new() });
+        }
+    }
+
+    for (position, item) in wanted.iter().enumerate() {
+        let found = items[position..].iter().position(|existing| key(existing) == key(item));
+        match found {
+            Some(0) if items[position] == *item => {}
+            // Same item, new contents
+            Some(0) => apply(&mut items, Splice { position: position as u32, removed: 1, added: vec![item.clone()] }),
+            // Further down: take it out of there and put it here
+            Some(offset) => {
+                apply(&mut items, Splice { position: (position + offset) as u32, removed: 1, added: Vec::new() });
+                apply(&mut items, Splice { position: position as u32, removed: 0, added: vec![item.clone()] });
+            }
+            None => apply(&mut items, Splice { position: position as u32, removed: 0, added: vec![item.clone()] }),
+        }
+    }
+
+    // Duplicate keys in `current` would otherwise linger past the end
+    if items.len() > wanted.len() {
+        let extra = (items.len() - wanted.len()) as u32;
+        apply(&mut items, Splice { position: wanted.len() as u32, removed: extra, added: Vec::new() });
+    }
+    splices
+}
@@ -0,0 +1,81 @@
+// Converts the Markdown subset used in notes to Pango markup for a label: `#` headings,
+// `-`/`*` bullets, **bold**, *italic* or _italic_, `code` and [links](url). Anything else,
+// including unmatched markers, is shown as typed.
+pub fn to_pango(text: &str) -> String {
+    text.lines().map(line_to_pango).collect::<Vec<_>>().join("\n")
+}
+
+fn line_to_pango(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=3).contains(&level)
+        && let Some(heading) = trimmed[level..].strip_prefix(' ')
+    {
+        let heading = inline(heading.trim());
+        return if level == 1 {
+            format!("<span size=\"large\"><b>{}</b></span>", heading)
+        } else {
+            format!("<b>{}</b>", heading)
+        };
+    }
+
+    match trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+        Some(item) => format!("• {}", inline(item)),
+        None => inline(line),
+    }
+}
+
+// Markers only pair up within one span, so the produced tags always nest properly
+fn inline(text: &str) -> String {
+    let mut markup = String::new();
+    let mut rest = text;
+    let mut previous: Option<char> = None;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some((converted, remaining)) = span(rest, previous) {
+            markup.push_str(&converted);
+            previous = rest[..rest.len() - remaining.len()].chars().last();
+            rest = remaining;
+            continue;
+        }
+        markup.push_str(&escape(c.encode_utf8(&mut [0; 4])));
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    markup
+}
+
+// The formatted span `text` starts with, if any, and what follows it
+fn span(text: &str, previous: Option<char>) -> Option<(String, &str)> {
+    if let Some(after) = text.strip_prefix('`') {
+        let (code, rest) = after.split_once('`')?;
+        return Some((format!("<tt>{}</tt>", escape(code)), rest));
+    }
+    if let Some(after) = text.strip_prefix("**") {
+        let (bold, rest) = after.split_once("**")?;
+        return (!bold.is_empty()).then(|| (format!("<b>{}</b>", inline(bold)), rest));
+    }
+    if let Some(marker) = text.chars().next().filter(|&c| c == '*' || c == '_') {
+        // snake_case names are not emphasis
+        if marker == '_' && previous.is_some_and(char::is_alphanumeric) {
+            return None;
+        }
+        let (italic, rest) = text[1..].split_once(marker)?;
+        return (!italic.is_empty()).then(|| (format!("<i>{}</i>", inline(italic)), rest));
+    }
+    if let Some(after) = text.strip_prefix('[') {
+        let (label, after) = after.split_once("](")?;
+        let (url, rest) = after.split_once(')')?;
+        let link = format!("<a href=\"{}\">{}</a>", escape(url), inline(label));
+        return Some((link, rest));
+    }
+    None
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
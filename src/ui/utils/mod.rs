@@ -1,2 +1,9 @@
 pub mod api_handler;
-pub mod error_display;
\ No newline at end of file
+pub mod app_settings;
+pub mod error_display;
+pub mod image_loader;
+pub mod labels;
+pub mod list_diff;
+pub mod markdown;
+pub mod paged_listing;
+pub mod toast;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use libadwaita::gtk;
+use gtk::prelude::*;
+use gtk::glib;
+use std::cell::RefCell;
+use std::future::Future;
+
+// Distance in pixels from the bottom of the scrolled grid at which the next page is requested
+pub const LOAD_MORE_THRESHOLD: f64 = 400.0;
+
+// How far an infinitely scrolled listing has been paged; kept inside the listing's own state
+#[derive(Default)]
+pub struct Paging {
+    pub next_page: u32,
+    pub has_next_page: bool,
+    pub loading: bool,
+    // Bumped whenever a new listing starts so responses for an older one are dropped
+    pub generation: u32,
+    // The listing or page request in flight, aborted when a newer one starts
+    task: Option<glib::JoinHandle<()>>,
+}
+
+impl Paging {
+    // Resets paging for a new listing and returns its generation
+    pub fn restart(&mut self) -> u32 {
+        self.generation = self.generation.wrapping_add(1);
+        self.next_page = 1;
+        self.has_next_page = false;
+        self.loading = true;
+        self.generation
+    }
+
+    // Claims the next page to load, with the generation it belongs to, unless one is already
+    // loading or there is none
+    pub fn begin_next_page(&mut self) -> Option<(u32, u32)> {
+        if self.loading || !self.has_next_page {
+            return None;
+        }
+        self.loading = true;
+        Some((self.next_page, self.generation))
+    }
+
+    pub fn record_page(&mut self, page: u32, has_next_page: bool) {
+        self.next_page = page + 1;
+        self.has_next_page = has_next_page;
+    }
+
+    pub fn is_current(&self, generation: u32) -> bool {
+        self.generation == generation
+    }
+}
+
+// Runs a listing task in place of the one in flight. Aborting drops the older task's
+// request, so its results can never be rendered over the newer ones.
+pub fn spawn_listing<S, F>(state: &RefCell<S>, future: F)
+where
+    S: AsMut<Paging>,
+    F: Future<Output = ()> + 'static,
+{
+    let previous = state.borrow_mut().as_mut().task.take();
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+
+    let handle = glib::MainContext::default().spawn_local(future);
+    state.borrow_mut().as_mut().task = Some(handle);
+}
+
+// Calls `on_scroll` whenever `window` scrolls, and also when its content grows, in case
+// one page does not fill the window
+pub fn connect_scroll<F>(window: &gtk::ScrolledWindow, on_scroll: F)
+where
+    F: Fn(&gtk::Adjustment) + Clone + 'static,
+{
+    let adjustment = window.vadjustment();
+    adjustment.connect_value_changed(on_scroll.clone());
+    adjustment.connect_changed(on_scroll);
+}
+
+pub fn near_bottom(adjustment: &gtk::Adjustment) -> bool {
+    let distance_to_bottom = adjustment.upper() - (adjustment.value() + adjustment.page_size());
+    distance_to_bottom <= LOAD_MORE_THRESHOLD
+}
@@ -0,0 +1,18 @@
+use libadwaita as adw;
+use adw::prelude::*;
+use libadwaita::gtk;
+
+// The toast overlay wrapping `widget`, if it is currently inside one
+pub fn overlay_for(widget: &impl IsA<gtk::Widget>) -> Option<adw::ToastOverlay> {
+    widget
+        .ancestor(adw::ToastOverlay::static_type())
+        .and_downcast::<adw::ToastOverlay>()
+}
+
+// Builds a toast whose title is shown verbatim rather than parsed as markup
+pub fn plain_toast(title: &str) -> adw::Toast {
+    adw::Toast::builder()
+        .title(title)
+        .use_markup(false)
+        .build()
+}
\ No newline at end of file
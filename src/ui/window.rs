@@ -1,11 +1,13 @@
 use libadwaita as adw;
-use libadwaita::gtk;
-use gtk::prelude::*;
+use adw::prelude::*;
+use libadwaita::gtk::glib;
+use log::error;
 
 use crate::ui::headerbar::WaifuHeaderBar;
 use crate::ui::content::WaifuContent;
 use crate::ui::components::window_builder::WindowBuilder;
 use crate::ui::components::signal_connector::SignalConnector;
+use crate::ui::utils::app_settings::{AppSettings, WindowState};
 
 #[allow(dead_code)]
 pub struct WaifuWindow {
@@ -17,11 +19,35 @@ impl WaifuWindow {
         let header_bar = WaifuHeaderBar::new();
         let content = WaifuContent::new();
         
-        let window = WindowBuilder::create_window(app, &header_bar, &content);
-        SignalConnector::connect_signals(&window, &header_bar, &content);
+        let navigation_view = adw::NavigationView::new();
+
+        let window = WindowBuilder::create_window(app, &header_bar, &content, &navigation_view);
+        SignalConnector::connect_signals(&window, &header_bar, &content, &navigation_view);
+
+        // Reopen where the user left off
+        content.set_visible_page(&AppSettings::shared().window_state().page);
+        window.connect_close_request(move |window| {
+            Self::save_state(window, &content);
+            glib::Propagation::Proceed
+        });
 
         Self {
             window,
         }
     }
+
+    fn save_state(window: &adw::ApplicationWindow, content: &WaifuContent) {
+        // The default size tracks the unmaximized size, so un-maximizing after a restore still works
+        let (width, height) = window.default_size();
+        let state = WindowState {
+            width,
+            height,
+            maximized: window.is_maximized(),
+            page: content.visible_page(),
+            scroll: content.explore_page.characters_window.vadjustment().value(),
+        };
+        if let Err(e) = AppSettings::shared().save_window_state(&state) {
+            error!("Failed to save the window state: {}", e);
+        }
+    }
 }
@@ -0,0 +1,327 @@
+use log::kv::{Key, Value, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::utils::log_file::RotatingFile;
+
+// Filter spec in the style of RUST_LOG, e.g. `warn,waifu_viewer::api=debug`.
+// When set it wins over the verbose logging setting.
+pub const LOG_ENV: &str = "WAIFU_VIEWER_LOG";
+const DEFAULT_FILTER: &str = "warn,waifu_viewer=info";
+const VERBOSE_FILTER: &str = "info,waifu_viewer=debug";
+
+const LOG_FILE: &str = "waifu-viewer.log";
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+const KEPT_FILES: usize = 3;
+// Lines kept in memory for the Debug log window
+const RECENT_LINES: usize = 2000;
+// Lines a slow Debug log window may fall behind before new ones are dropped for it
+const SUBSCRIBER_BACKLOG: usize = 1000;
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+// Logging preferences; the app keeps them in GSettings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogSettings {
+    // Debug messages from the app and info from its libraries
+    pub verbose: bool,
+}
+
+// Per-module levels; the longest module prefix matching a record's target decides
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    // Comma-separated `level` or `module=level` directives
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Self {
+            default: LevelFilter::Error,
+            modules: Vec::new(),
+        };
+        for directive in spec.split(',').map(str::trim).filter(|directive| !directive.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let module = module.trim();
+                    if module.is_empty() {
+                        return Err(format!("Missing module name in `{}`", directive));
+                    }
+                    let level = Self::parse_level(level)?;
+                    filter.modules.retain(|(existing, _)| existing != module);
+                    filter.modules.push((module.to_string(), level));
+                }
+                None => filter.default = Self::parse_level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+
+    fn parse_level(level: &str) -> Result<LevelFilter, String> {
+        level
+            .trim()
+            .parse()
+            .map_err(|_| format!("Unknown log level `{}`", level.trim()))
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .filter(|(module, _)| {
+                target == module
+                    || target.strip_prefix(module.as_str()).is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        level <= self.level_for(target)
+    }
+
+    // The most verbose level any module may log at
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+// One formatted record, as written to stderr, the log file and the Debug log window
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub time: SystemTime,
+    pub level: Level,
+    pub target: String,
+    // The message followed by any structured key=value pairs
+    pub message: String,
+}
+
+impl LogLine {
+    fn from_record(record: &Record) -> Self {
+        let mut message = record.args().to_string();
+        let _ = record.key_values().visit(&mut KeyValueWriter(&mut message));
+        Self {
+            time: SystemTime::now(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:<5} {}: {}", format_time(self.time), self.level, self.target, self.message)
+    }
+}
+
+// Appends ` key=value` for every structured field of a record
+struct KeyValueWriter<'a>(&'a mut String);
+
+impl<'kvs> VisitSource<'kvs> for KeyValueWriter<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+        let _ = write!(self.0, " {}={}", key, value);
+        Ok(())
+    }
+}
+
+// UTC time as `2025-01-31T12:34:56.789Z`
+fn format_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let seconds_of_day = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+// Days since 1970-01-01 to a proleptic Gregorian date (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = (if month_index < 10 { month_index + 3 } else { month_index - 9 }) as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+struct Logger {
+    filter: RwLock<LogFilter>,
+    // True when LOG_ENV chose the filter, which the settings toggle then leaves alone
+    from_env: bool,
+    file: Mutex<Option<RotatingFile>>,
+    recent: Mutex<VecDeque<LogLine>>,
+    subscribers: Mutex<Vec<async_channel::Sender<LogLine>>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        read(&self.filter).enabled(metadata.target(), metadata.level())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = LogLine::from_record(record);
+        let text = line.to_string();
+        let _ = writeln!(std::io::stderr(), "{}", text);
+
+        if let Some(file) = lock(&self.file).as_mut()
+            && let Err(e) = file.write_line(&text)
+        {
+            let _ = writeln!(std::io::stderr(), "Failed to write log file {}: {}", file.path().display(), e);
+        }
+
+        lock(&self.subscribers).retain(|sender| match sender.try_send(line.clone()) {
+            Ok(()) | Err(async_channel::TrySendError::Full(_)) => true,
+            Err(async_channel::TrySendError::Closed(_)) => false,
+        });
+
+        let mut recent = lock(&self.recent);
+        if recent.len() >= RECENT_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(line);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = lock(&self.file).as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn read<T>(lock: &RwLock<T>) -> std::sync::RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn settings_filter(verbose: bool) -> LogFilter {
+    let spec = if verbose { VERBOSE_FILTER } else { DEFAULT_FILTER };
+    LogFilter::parse(spec).expect("built-in log filters are valid")
+}
+
+// Directory for the log files: the XDG state directory, else the cache directory
+pub fn log_dir() -> Option<PathBuf> {
+    let mut dir = dirs::state_dir().or_else(dirs::cache_dir)?;
+    dir.push("waifu-viewer");
+    dir.push("logs");
+    Some(dir)
+}
+
+// Installs the logger for the whole process. Later calls do nothing.
+pub fn init(settings: &LogSettings, log_to_file: bool) {
+    let env_spec = std::env::var(LOG_ENV).ok().filter(|spec| !spec.trim().is_empty());
+    let env_filter = env_spec.as_deref().map(LogFilter::parse);
+    let from_env = matches!(env_filter, Some(Ok(_)));
+    let filter = match &env_filter {
+        Some(Ok(filter)) => filter.clone(),
+        _ => settings_filter(settings.verbose),
+    };
+
+    let mut file_error = None;
+    let file = if log_to_file {
+        log_dir().and_then(|dir| match RotatingFile::open(&dir.join(LOG_FILE), MAX_FILE_BYTES, KEPT_FILES) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                file_error = Some(format!("{}: {}", dir.display(), e));
+                None
+            }
+        })
+    } else {
+        None
+    };
+
+    let max_level = filter.max_level();
+    let logger = Logger {
+        filter: RwLock::new(filter),
+        from_env,
+        file: Mutex::new(file),
+        recent: Mutex::new(VecDeque::new()),
+        subscribers: Mutex::new(Vec::new()),
+    };
+    if LOGGER.set(logger).is_err() {
+        return;
+    }
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if log::set_logger(logger).is_err() {
+        return;
+    }
+    log::set_max_level(max_level);
+
+    if let Some(Err(e)) = env_filter {
+        log::warn!("Ignoring {}: {}", LOG_ENV, e);
+    }
+    if let Some(e) = file_error {
+        log::warn!("Could not open the log file in {}", e);
+    }
+}
+
+// Applies the verbose logging setting. Returns false when LOG_ENV overrides it.
+pub fn set_verbose(verbose: bool) -> bool {
+    let Some(logger) = LOGGER.get() else {
+        return false;
+    };
+    if logger.from_env {
+        return false;
+    }
+
+    let filter = settings_filter(verbose);
+    log::set_max_level(filter.max_level());
+    *logger.filter.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = filter;
+    true
+}
+
+// Whether LOG_ENV is in charge of the filter
+pub fn filter_from_env() -> bool {
+    LOGGER.get().is_some_and(|logger| logger.from_env)
+}
+
+// The most recent lines, oldest first
+pub fn recent_lines() -> Vec<LogLine> {
+    LOGGER
+        .get()
+        .map(|logger| lock(&logger.recent).iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+// Every line logged from now on. Dropping the receiver unsubscribes.
+pub fn subscribe() -> async_channel::Receiver<LogLine> {
+    let (sender, receiver) = async_channel::bounded(SUBSCRIBER_BACKLOG);
+    if let Some(logger) = LOGGER.get() {
+        lock(&logger.subscribers).push(sender);
+    }
+    receiver
+}
+
+pub fn log_file_path() -> Option<PathBuf> {
+    let logger = LOGGER.get()?;
+    lock(&logger.file).as_ref().map(|file| file.path().to_path_buf())
+}
@@ -1,2 +1,4 @@
 pub mod api_handler;
-pub mod error_display;
\ No newline at end of file
+pub mod error_display;
+pub mod log_file;
+pub mod logger;
\ No newline at end of file
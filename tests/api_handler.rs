@@ -0,0 +1,79 @@
+mod common;
+
+use std::collections::HashSet;
+
+use common::{MockResponse, MockServer};
+use waifu_viewer::api::jikan::JikanError;
+use waifu_viewer::api::search_query::CharacterSearchQuery;
+use waifu_viewer::utils::api_handler::ApiHandler;
+
+#[tokio::test]
+async fn lists_top_characters_through_the_client() {
+    let server = MockServer::start();
+    server.route("/v4/top/characters?page=2", MockResponse::fixture(200, "top_characters.json"));
+    let api_handler = ApiHandler::with_client(common::client_for(&server));
+
+    let response = api_handler.get_top_characters(2).await.unwrap();
+
+    assert_eq!(response.data[0].mal_id, 417);
+    assert_eq!(server.request_count("/v4/top/characters?page=2"), 1);
+}
+
+#[tokio::test]
+async fn searches_characters_by_name() {
+    let server = MockServer::start();
+    server.route("/v4/characters?q=lelouch&limit=25", MockResponse::fixture(200, "search_characters.json"));
+    let api_handler = ApiHandler::with_client(common::client_for(&server));
+
+    let query = CharacterSearchQuery::new().query("lelouch").limit(100);
+    let response = api_handler.search_characters(&query).await.unwrap();
+
+    let ids: Vec<u32> = response.data.iter().map(|character| character.mal_id).collect();
+    assert_eq!(ids, [417, 146135]);
+    assert!(!response.pagination.unwrap().has_next_page);
+}
+
+#[tokio::test]
+async fn passes_errors_through() {
+    let server = MockServer::start();
+    let api_handler = ApiHandler::with_client(common::client_for(&server));
+
+    let error = api_handler.get_character_full(999_999_999).await.unwrap_err();
+
+    assert!(matches!(error, JikanError::Http { .. }));
+}
+
+#[tokio::test]
+async fn random_batches_skip_excluded_and_repeated_characters() {
+    let server = MockServer::start();
+    let character = |mal_id: u32| {
+        let data = format!(
+            r#"{{"data":{{"mal_id":{id},"url":"https://myanimelist.net/character/{id}","images":{{"jpg":{{"image_url":null}}}},"name":"Character {id}","name_kanji":null,"nicknames":[],"favorites":0,"about":null}}}}"#,
+            id = mal_id
+        );
+        MockResponse::new(200, data)
+    };
+    for mal_id in [1, 2, 2, 3] {
+        server.route("/v4/random/characters", character(mal_id));
+    }
+    let api_handler = ApiHandler::with_client(common::client_for(&server));
+
+    let exclude: HashSet<u32> = [1].into_iter().collect();
+    let characters = api_handler.get_random_characters(2, &exclude).await.unwrap();
+
+    let ids: Vec<u32> = characters.iter().map(|character| character.mal_id).collect();
+    assert_eq!(ids, [2, 3]);
+}
+
+#[tokio::test]
+async fn voice_actors_are_listed_with_their_language() {
+    let server = MockServer::start();
+    server.route("/v4/characters/417/voices", MockResponse::fixture(200, "character_voices.json"));
+    let api_handler = ApiHandler::with_client(common::client_for(&server));
+
+    let voices = api_handler.get_character_voices(417).await.unwrap();
+
+    let cast: Vec<(&str, u32)> = voices.iter().map(|voice| (voice.language.as_str(), voice.person.mal_id)).collect();
+    assert_eq!(cast, [("Japanese", 87), ("English", 102)]);
+    assert_eq!(voices[0].person.name, "Fukuyama, Jun");
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use waifu_viewer::api::jikan::JikanResponse;
+use waifu_viewer::models::character::Character;
+
+fn top_characters() -> Vec<Character> {
+    let response: JikanResponse = serde_json::from_str(&common::fixture("top_characters.json")).unwrap();
+    response.data
+}
+
+#[test]
+fn maps_every_field_of_a_listing_entry() {
+    let lelouch = top_characters().remove(0);
+
+    assert_eq!(lelouch.mal_id, 417);
+    assert_eq!(lelouch.url, "https://myanimelist.net/character/417/Lelouch_Lamperouge");
+    assert_eq!(lelouch.name, "Lelouch Lamperouge");
+    assert_eq!(lelouch.name_kanji.as_deref(), Some("ルルーシュ・ランペルージ"));
+    assert_eq!(lelouch.nicknames, ["Lelouch vi Britannia", "Zero", "Lulu"]);
+    assert_eq!(lelouch.favorites, 175872);
+    assert!(lelouch.about.unwrap().starts_with("Birthday: December 5"));
+    assert_eq!(
+        lelouch.images.jpg.image_url.as_deref(),
+        Some("https://cdn.myanimelist.net/images/characters/8/406163.jpg")
+    );
+}
+
+#[test]
+fn missing_small_image_is_none() {
+    // Listings only send the regular jpg URL
+    for character in top_characters() {
+        assert_eq!(character.images.jpg.small_image_url, None);
+    }
+}
+
+#[test]
+fn null_fields_become_none() {
+    let response: JikanResponse = serde_json::from_str(&common::fixture("search_characters.json")).unwrap();
+    let obscure = &response.data[1];
+
+    assert_eq!(obscure.name_kanji, None);
+    assert_eq!(obscure.about, None);
+    assert!(obscure.nicknames.is_empty());
+}
+
+#[test]
+fn survives_a_serialization_round_trip() {
+    for character in top_characters() {
+        let json = serde_json::to_string(&character).unwrap();
+        let restored: Character = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, character);
+    }
+}
+
+#[test]
+fn rejects_entries_without_required_fields() {
+    let json = r#"{"mal_id": 1, "url": "https://myanimelist.net/character/1", "name": "No Images", "nicknames": [], "favorites": 0}"#;
+    assert!(serde_json::from_str::<Character>(json).is_err());
+}
+
+#[test]
+fn error_bodies_are_not_listings() {
+    for fixture in ["error_404.json", "error_429.json", "error_500.json"] {
+        assert!(serde_json::from_str::<JikanResponse>(&common::fixture(fixture)).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+// Shared helpers for the integration tests: recorded fixtures, a local HTTP stand-in
+// for Jikan and throwaway directories. Not every test binary uses every helper.
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use waifu_viewer::api::jikan::JikanClient;
+
+pub fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+pub fn fixture_bytes(name: &str) -> Vec<u8> {
+    std::fs::read(fixture_path(name)).unwrap_or_else(|e| panic!("missing fixture {}: {}", name, e))
+}
+
+pub fn fixture(name: &str) -> String {
+    String::from_utf8(fixture_bytes(name)).expect("fixture is UTF-8")
+}
+
+// An empty directory under the system temp dir, unique to this process and call
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "waifu-viewer-test-{}-{}-{}",
+        std::process::id(),
+        name,
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+// A client for the mock server without the on-disk response cache
+pub fn client_for(server: &MockServer) -> JikanClient {
+    JikanClient::builder()
+        .base_url(&server.base_url())
+        .timeout(Duration::from_secs(5))
+        .cache(None)
+        .build()
+        .expect("mock client builds")
+}
+
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    // Wait this long before answering, to trigger client timeouts
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            delay: None,
+        }
+    }
+
+    // A recorded Jikan response served as JSON
+    pub fn fixture(status: u16, name: &str) -> Self {
+        Self::new(status, fixture_bytes(name)).header("Content-Type", "application/json")
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    // Path and query exactly as requested, e.g. `/v4/characters?q=rem`
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Default)]
+struct Routes {
+    // Responses per request target, served in order; the last one repeats
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    requests: Vec<RecordedRequest>,
+}
+
+// Replays canned responses over plain HTTP on 127.0.0.1. Unknown targets get Jikan's
+// 404 body. The server lives until the test process exits.
+pub struct MockServer {
+    addr: SocketAddr,
+    routes: Arc<Mutex<Routes>>,
+}
+
+impl MockServer {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let addr = listener.local_addr().expect("mock server address");
+        let routes = Arc::new(Mutex::new(Routes::default()));
+
+        let accept_routes = routes.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = accept_routes.clone();
+                thread::spawn(move || {
+                    let _ = Self::serve(stream, &routes);
+                });
+            }
+        });
+
+        Self { addr, routes }
+    }
+
+    // Base URL to hand to the client, mirroring Jikan's `/v4` prefix
+    pub fn base_url(&self) -> String {
+        format!("http://{}/v4", self.addr)
+    }
+
+    pub fn url(&self, target: &str) -> String {
+        format!("http://{}{}", self.addr, target)
+    }
+
+    // Queues `response` for `target`, which starts with `/v4` for API routes
+    pub fn route(&self, target: &str, response: MockResponse) -> &Self {
+        self.routes
+            .lock()
+            .unwrap()
+            .responses
+            .entry(target.to_string())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.routes.lock().unwrap().requests.clone()
+    }
+
+    pub fn request_count(&self, target: &str) -> usize {
+        self.requests().iter().filter(|request| request.target == target).count()
+    }
+
+    fn serve(stream: TcpStream, routes: &Mutex<Routes>) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let target = request_line.split_whitespace().nth(1).unwrap_or("/").to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        let response = {
+            let mut routes = routes.lock().unwrap();
+            routes.requests.push(RecordedRequest { target: target.clone(), headers });
+            match routes.responses.get_mut(&target) {
+                Some(queue) if queue.len() > 1 => queue.pop_front(),
+                Some(queue) => queue.front().cloned(),
+                None => None,
+            }
+        };
+        let response = response.unwrap_or_else(|| MockResponse::fixture(404, "error_404.json"));
+
+        if let Some(delay) = response.delay {
+            thread::sleep(delay);
+        }
+
+        let mut stream = stream;
+        write!(stream, "HTTP/1.1 {} Mock\r\n", response.status)?;
+        for (name, value) in &response.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", response.body.len())?;
+        stream.write_all(&response.body)?;
+        stream.flush()
+    }
+}
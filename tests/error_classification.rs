@@ -0,0 +1,80 @@
+mod common;
+
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use common::{MockResponse, MockServer};
+use waifu_viewer::api::jikan::{JikanClient, JikanError};
+use waifu_viewer::ui::handlers::{ErrorNotice, SearchHandler};
+
+const CONTEXT: &str = "Error loading characters";
+
+#[tokio::test]
+async fn refused_connections_are_offline() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let client = JikanClient::builder()
+        .base_url(&format!("http://127.0.0.1:{}/v4", port))
+        .cache(None)
+        .build()
+        .unwrap();
+
+    let error = client.get_top_characters(1).await.unwrap_err();
+
+    assert_eq!(SearchHandler::classify_error(&error, CONTEXT), ErrorNotice::Offline);
+}
+
+#[tokio::test]
+async fn timeouts_are_offline() {
+    let server = MockServer::start();
+    server.route(
+        "/v4/top/characters?page=1",
+        MockResponse::fixture(200, "top_characters.json").delay(Duration::from_secs(2)),
+    );
+    let client = JikanClient::builder()
+        .base_url(&server.base_url())
+        .timeout(Duration::from_millis(200))
+        .cache(None)
+        .build()
+        .unwrap();
+
+    let error = client.get_top_characters(1).await.unwrap_err();
+
+    assert_eq!(SearchHandler::classify_error(&error, CONTEXT), ErrorNotice::Offline);
+}
+
+#[test]
+fn transport_failures_are_offline() {
+    let error = JikanError::Transport("connection reset".to_string());
+    assert_eq!(SearchHandler::classify_error(&error, CONTEXT), ErrorNotice::Offline);
+}
+
+#[test]
+fn rate_limiting_has_its_own_notice() {
+    assert_eq!(
+        SearchHandler::classify_error(&JikanError::RateLimited, CONTEXT),
+        ErrorNotice::RateLimited
+    );
+}
+
+#[test]
+fn http_errors_show_the_status_with_context() {
+    let error = JikanError::Http {
+        status: StatusCode::NOT_FOUND,
+        body: common::fixture("error_404.json"),
+    };
+
+    assert_eq!(
+        SearchHandler::classify_error(&error, CONTEXT),
+        ErrorNotice::Message("Error loading characters: HTTP error: 404 Not Found".to_string())
+    );
+}
+
+#[test]
+fn parse_errors_show_the_message() {
+    let error = JikanError::from(serde_json::from_str::<u32>("<html>").unwrap_err());
+
+    match SearchHandler::classify_error(&error, CONTEXT) {
+        ErrorNotice::Message(message) => assert!(message.starts_with("Error loading characters: JSON parsing error")),
+        other => panic!("expected a message, got {:?}", other),
+    }
+}
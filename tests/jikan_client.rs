@@ -0,0 +1,393 @@
+mod common;
+
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use common::{MockResponse, MockServer};
+use waifu_viewer::api::client_builder::JikanSettings;
+use waifu_viewer::api::image_cache::ImageCache;
+use waifu_viewer::api::jikan::{JikanClient, JikanError};
+use waifu_viewer::api::response_cache::{CacheEntry, ResponseCache};
+use waifu_viewer::api::search_query::{CharacterOrderBy, CharacterSearchQuery, SortDirection};
+use waifu_viewer::api::transport::{JikanTransport, TransportFuture, TransportRequest, TransportResponse};
+
+// A cache in a fresh directory that treats every entry as stale, so each call revalidates
+fn expiring_cache(name: &str) -> ResponseCache {
+    let mut cache = ResponseCache::with_dir(common::temp_dir(name), 1024 * 1024);
+    cache.set_default_ttl(Duration::ZERO);
+    cache.set_ttl("/top/", Duration::ZERO);
+    cache.set_ttl("/characters?", Duration::ZERO);
+    cache
+}
+
+#[tokio::test]
+async fn top_characters_are_parsed_from_the_recorded_response() {
+    let server = MockServer::start();
+    server.route("/v4/top/characters?page=1", MockResponse::fixture(200, "top_characters.json"));
+
+    let response = common::client_for(&server).get_top_characters(1).await.unwrap();
+
+    let names: Vec<&str> = response.data.iter().map(|character| character.name.as_str()).collect();
+    assert_eq!(names, ["Lelouch Lamperouge", "Levi", "L Lawliet"]);
+    let pagination = response.pagination.unwrap();
+    assert!(pagination.has_next_page);
+    assert_eq!(pagination.last_visible_page, 5943);
+}
+
+#[tokio::test]
+async fn search_parameters_are_encoded() {
+    let server = MockServer::start();
+    server.route(
+        "/v4/characters?q=Lelouch+%26+Zero&page=2&order_by=favorites&sort=desc",
+        MockResponse::fixture(200, "search_characters.json"),
+    );
+
+    let query = CharacterSearchQuery::new()
+        .query("  Lelouch & Zero ")
+        .page(2)
+        .order_by(CharacterOrderBy::Favorites)
+        .sort(SortDirection::Desc);
+    let response = common::client_for(&server).search_characters(&query).await.unwrap();
+
+    assert_eq!(response.data.len(), 2);
+    assert_eq!(response.data[1].name_kanji, None);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn not_found_keeps_the_status_and_body() {
+    let server = MockServer::start();
+
+    let error = common::client_for(&server).get_character(999_999_999).await.unwrap_err();
+
+    match error {
+        JikanError::Http { status, body } => {
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert!(body.contains("Resource does not exist"));
+        }
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_requests_are_retried() {
+    let server = MockServer::start();
+    server
+        .route("/v4/top/characters?page=1", MockResponse::fixture(429, "error_429.json").header("Retry-After", "0"))
+        .route("/v4/top/characters?page=1", MockResponse::fixture(200, "top_characters.json"));
+
+    let response = common::client_for(&server).get_top_characters(1).await.unwrap();
+
+    assert_eq!(response.data.len(), 3);
+    assert_eq!(server.request_count("/v4/top/characters?page=1"), 2);
+}
+
+#[tokio::test]
+async fn persistent_rate_limiting_gives_up() {
+    let server = MockServer::start();
+    server.route("/v4/top/characters?page=3", MockResponse::fixture(429, "error_429.json").header("Retry-After", "0"));
+
+    let error = common::client_for(&server).get_top_characters(3).await.unwrap_err();
+
+    assert!(matches!(error, JikanError::RateLimited));
+    // The first attempt plus three retries
+    assert_eq!(server.request_count("/v4/top/characters?page=3"), 4);
+}
+
+#[tokio::test]
+async fn server_errors_surface_after_retrying() {
+    let server = MockServer::start();
+    server.route("/v4/characters/417", MockResponse::fixture(500, "error_500.json").header("Retry-After", "0"));
+
+    let error = common::client_for(&server).get_character(417).await.unwrap_err();
+
+    match error {
+        JikanError::Http { status, body } => {
+            assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert!(body.contains("ParserException"));
+        }
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+    assert_eq!(server.request_count("/v4/characters/417"), 4);
+}
+
+#[tokio::test]
+async fn malformed_bodies_are_parse_errors() {
+    let server = MockServer::start();
+    server.route("/v4/top/characters?page=1", MockResponse::new(200, "<html>Bad Gateway</html>"));
+
+    let error = common::client_for(&server).get_top_characters(1).await.unwrap_err();
+
+    assert!(matches!(error, JikanError::JsonParsing(_)));
+}
+
+#[tokio::test]
+async fn stale_entries_are_revalidated_with_their_etag() {
+    let server = MockServer::start();
+    server
+        .route("/v4/top/characters?page=1", MockResponse::fixture(200, "top_characters.json").header("ETag", "\"top-1\""))
+        .route("/v4/top/characters?page=1", MockResponse::new(304, ""));
+
+    let client = JikanClient::builder()
+        .base_url(&server.base_url())
+        .cache(Some(expiring_cache("revalidate")))
+        .build()
+        .unwrap();
+
+    let first = client.get_top_characters(1).await.unwrap();
+    let second = client.get_top_characters(1).await.unwrap();
+
+    assert_eq!(first.data, second.data);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].header("If-None-Match"), None);
+    assert_eq!(requests[1].header("If-None-Match"), Some("\"top-1\""));
+}
+
+#[tokio::test]
+async fn fresh_entries_skip_the_network() {
+    let server = MockServer::start();
+    server.route("/v4/characters?q=lelouch", MockResponse::fixture(200, "search_characters.json"));
+
+    let cache = ResponseCache::with_dir(common::temp_dir("fresh"), 1024 * 1024);
+    let client = JikanClient::builder()
+        .base_url(&server.base_url())
+        .cache(Some(cache))
+        .build()
+        .unwrap();
+
+    let query = CharacterSearchQuery::new().query("lelouch");
+    client.search_characters(&query).await.unwrap();
+    let cached = client.search_characters(&query).await.unwrap();
+
+    assert_eq!(cached.data.len(), 2);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn cached_responses_cover_for_server_errors() {
+    let server = MockServer::start();
+    server
+        .route("/v4/top/characters?page=1", MockResponse::fixture(200, "top_characters.json"))
+        .route("/v4/top/characters?page=1", MockResponse::fixture(500, "error_500.json").header("Retry-After", "0"));
+
+    let client = JikanClient::builder()
+        .base_url(&server.base_url())
+        .cache(Some(expiring_cache("stale")))
+        .build()
+        .unwrap();
+
+    client.get_top_characters(1).await.unwrap();
+    let stale = client.get_top_characters(1).await.unwrap();
+
+    assert_eq!(stale.data.len(), 3);
+}
+
+#[tokio::test]
+async fn unreachable_servers_are_network_errors() {
+    // Bind and drop a listener to find a port nothing listens on
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let client = JikanClient::builder()
+        .base_url(&format!("http://127.0.0.1:{}/v4", port))
+        .cache(None)
+        .build()
+        .unwrap();
+
+    let error = client.get_character(417).await.unwrap_err();
+
+    match error {
+        JikanError::Network(e) => assert!(e.is_connect()),
+        other => panic!("expected a network error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn slow_responses_time_out() {
+    let server = MockServer::start();
+    server.route(
+        "/v4/characters/417",
+        MockResponse::fixture(200, "top_characters.json").delay(Duration::from_secs(2)),
+    );
+
+    let client = JikanClient::builder()
+        .base_url(&server.base_url())
+        .timeout(Duration::from_millis(200))
+        .cache(None)
+        .build()
+        .unwrap();
+
+    let error = client.get_character(417).await.unwrap_err();
+
+    match error {
+        JikanError::Network(e) => assert!(e.is_timeout()),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn listing_preferences_add_limit_and_sfw() {
+    let server = MockServer::start();
+    let empty_page = r#"{"pagination": null, "data": []}"#;
+    server
+        .route("/v4/top/characters?page=1&limit=10", MockResponse::fixture(200, "top_characters.json"))
+        .route("/v4/top/anime?page=2&limit=10&sfw=true", MockResponse::new(200, empty_page))
+        .route("/v4/manga?q=berserk&page=1&limit=10&sfw=true", MockResponse::new(200, empty_page));
+
+    let client = JikanClient::builder()
+        .base_url(&server.base_url())
+        .page_size(10)
+        .sfw(true)
+        .cache(None)
+        .build()
+        .unwrap();
+
+    // Character listings have no adult filter on Jikan's side
+    assert_eq!(client.get_top_characters(1).await.unwrap().data.len(), 3);
+    assert!(client.get_top_anime(2).await.unwrap().data.is_empty());
+    assert!(client.search_manga("berserk", 1).await.unwrap().data.is_empty());
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn settings_fall_back_field_by_field() {
+    let preferences = JikanSettings {
+        timeout_secs: Some(5),
+        page_size: Some(10),
+        sfw: Some(false),
+        ..JikanSettings::default()
+    };
+    let file = JikanSettings {
+        timeout_secs: Some(60),
+        proxy: Some("http://proxy.example:3128".to_string()),
+        sfw: Some(true),
+        ..JikanSettings::default()
+    };
+
+    let merged = preferences.or(file);
+
+    assert_eq!(merged.timeout_secs, Some(5));
+    assert_eq!(merged.page_size, Some(10));
+    assert_eq!(merged.sfw, Some(false));
+    assert_eq!(merged.proxy.as_deref(), Some("http://proxy.example:3128"));
+    assert_eq!(merged.base_url, None);
+}
+
+#[test]
+fn invalid_configuration_is_rejected() {
+    assert!(matches!(
+        JikanClient::builder().base_url("not a url").build(),
+        Err(JikanError::InvalidRequest(_))
+    ));
+    assert!(matches!(
+        JikanClient::builder().proxy("::not a proxy::").build(),
+        Err(JikanError::InvalidRequest(_))
+    ));
+}
+
+// Serves one canned body for every request without touching the network
+struct CannedTransport {
+    status: StatusCode,
+    body: String,
+}
+
+impl JikanTransport for CannedTransport {
+    fn send<'a>(&'a self, request: &'a TransportRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            assert!(request.url.starts_with("https://canned.invalid/v4/"));
+            Ok(TransportResponse::new(self.status, self.body.clone()))
+        })
+    }
+}
+
+// Fails every request as if the network were down
+struct OfflineTransport;
+
+impl JikanTransport for OfflineTransport {
+    fn send<'a>(&'a self, _request: &'a TransportRequest) -> TransportFuture<'a> {
+        Box::pin(async { Err(JikanError::Transport("offline".to_string())) })
+    }
+}
+
+#[tokio::test]
+async fn custom_transports_replace_http() {
+    let client = JikanClient::builder()
+        .base_url("https://canned.invalid/v4")
+        .transport(CannedTransport {
+            status: StatusCode::OK,
+            body: common::fixture("search_characters.json"),
+        })
+        .cache(None)
+        .build()
+        .unwrap();
+
+    let response = client.search_characters(&CharacterSearchQuery::new().query("lelouch")).await.unwrap();
+
+    assert_eq!(response.data[0].mal_id, 417);
+}
+
+#[tokio::test]
+async fn transport_failures_fall_back_to_the_cache() {
+    let dir = common::temp_dir("offline");
+    let mut cache = ResponseCache::with_dir(dir.clone(), 1024 * 1024);
+    cache.set_ttl("/top/", Duration::ZERO);
+
+    let online = JikanClient::builder()
+        .base_url("https://canned.invalid/v4")
+        .transport(CannedTransport {
+            status: StatusCode::OK,
+            body: common::fixture("top_characters.json"),
+        })
+        .cache(Some(cache))
+        .build()
+        .unwrap();
+    online.get_top_characters(1).await.unwrap();
+
+    let mut cache = ResponseCache::with_dir(dir, 1024 * 1024);
+    cache.set_ttl("/top/", Duration::ZERO);
+    let offline = JikanClient::builder()
+        .base_url("https://canned.invalid/v4")
+        .transport(OfflineTransport)
+        .cache(Some(cache))
+        .build()
+        .unwrap();
+
+    let stale = offline.get_top_characters(1).await.unwrap();
+    assert_eq!(stale.data.len(), 3);
+    assert!(matches!(
+        offline.get_top_characters(2).await,
+        Err(JikanError::Transport(_))
+    ));
+}
+
+#[tokio::test]
+async fn stores_past_the_limit_evict_older_entries() {
+    let dir = common::temp_dir("evict");
+    let cache = ResponseCache::with_dir(dir.clone(), 3000);
+    for page in 1..=6 {
+        let key = format!("/top/characters?page={}", page);
+        cache.store(&CacheEntry::new(&key, None, "x".repeat(900))).await.unwrap();
+    }
+
+    let sizes: Vec<u64> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .collect();
+    assert!(sizes.len() < 6);
+    assert!(sizes.iter().sum::<u64>() <= 3000);
+}
+
+#[tokio::test]
+async fn images_past_the_limit_evict_older_ones() {
+    let dir = common::temp_dir("evict-images");
+    let cache = ImageCache::with_dir(dir.clone(), 3000);
+    for index in 1..=6 {
+        cache.store(&format!("https://cdn.invalid/{}.jpg", index), vec![0; 900]).await.unwrap();
+    }
+
+    let sizes: Vec<u64> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .collect();
+    assert!(sizes.len() < 6);
+    assert!(sizes.iter().sum::<u64>() <= 3000);
+}
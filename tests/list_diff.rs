@@ -0,0 +1,55 @@
+use waifu_viewer::ui::utils::list_diff::{self, Splice};
+
+// (id, contents) pairs standing in for favorite records
+type Item = (u32, &'static str);
+
+fn apply(current: &[Item], splices: Vec<Splice<Item>>) -> Vec<Item> {
+    let mut items = current.to_vec();
+    for splice in splices {
+        let position = splice.position as usize;
+        items.splice(position..position + splice.removed as usize, splice.added);
+    }
+    items
+}
+
+fn sync(current: &[Item], wanted: &[Item]) -> (Vec<Item>, usize) {
+    let splices = list_diff::splices(current, wanted, |item| item.0);
+    let count = splices.len();
+    (apply(current, splices), count)
+}
+
+#[test]
+fn an_unchanged_list_needs_no_splices() {
+    let items = [(1, "a"), (2, "b"), (3, "c")];
+    assert_eq!(sync(&items, &items), (items.to_vec(), 0));
+}
+
+#[test]
+fn reordered_favorites_are_moved_not_duplicated() {
+    let current = [(1, "a"), (2, "b"), (3, "c"), (4, "d")];
+    let wanted = [(3, "c"), (1, "a"), (4, "d"), (2, "b")];
+
+    let (synced, _) = sync(&current, &wanted);
+
+    assert_eq!(synced, wanted);
+}
+
+#[test]
+fn additions_removals_and_edits_are_applied_in_place() {
+    let current = [(1, "a"), (2, "b"), (3, "c")];
+    let wanted = [(5, "e"), (1, "a"), (3, "c2")];
+
+    let (synced, count) = sync(&current, &wanted);
+
+    assert_eq!(synced, wanted);
+    // Remove 2, insert 5, replace 3; 1 stays as it was
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn duplicate_entries_are_collapsed() {
+    let current = [(1, "a"), (1, "a"), (2, "b")];
+    let wanted = [(2, "b"), (1, "a")];
+
+    assert_eq!(sync(&current, &wanted).0, wanted);
+}
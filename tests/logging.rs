@@ -0,0 +1,88 @@
+mod common;
+
+use log::{Level, LevelFilter};
+
+use waifu_viewer::utils::log_file::RotatingFile;
+use waifu_viewer::utils::logger::{self, LogFilter, LogSettings};
+
+#[test]
+fn filters_pick_the_longest_matching_module() {
+    let filter = LogFilter::parse("warn, waifu_viewer=info, waifu_viewer::api=trace").unwrap();
+
+    assert_eq!(filter.level_for("reqwest::connect"), LevelFilter::Warn);
+    assert_eq!(filter.level_for("waifu_viewer"), LevelFilter::Info);
+    assert_eq!(filter.level_for("waifu_viewer::ui::handlers"), LevelFilter::Info);
+    assert_eq!(filter.level_for("waifu_viewer::api::jikan"), LevelFilter::Trace);
+    // Module names match whole path segments only
+    assert_eq!(filter.level_for("waifu_viewer_extra"), LevelFilter::Warn);
+    assert_eq!(filter.max_level(), LevelFilter::Trace);
+
+    assert!(filter.enabled("waifu_viewer::api", Level::Debug));
+    assert!(!filter.enabled("hyper", Level::Info));
+}
+
+#[test]
+fn a_bare_level_applies_everywhere() {
+    let filter = LogFilter::parse("DEBUG").unwrap();
+    assert_eq!(filter.level_for("anything::at::all"), LevelFilter::Debug);
+
+    // Without a default only errors get through
+    let filter = LogFilter::parse("waifu_viewer=off").unwrap();
+    assert_eq!(filter.level_for("gtk"), LevelFilter::Error);
+    assert_eq!(filter.level_for("waifu_viewer::storage"), LevelFilter::Off);
+}
+
+#[test]
+fn invalid_filters_are_rejected() {
+    assert!(LogFilter::parse("loud").is_err());
+    assert!(LogFilter::parse("waifu_viewer=loud").is_err());
+    assert!(LogFilter::parse("=debug").is_err());
+}
+
+#[test]
+fn log_files_rotate_and_keep_a_few_generations() {
+    let dir = common::temp_dir("rotate");
+    let path = dir.join("app.log");
+    let mut file = RotatingFile::open(&path, 20, 2).unwrap();
+
+    for line in ["first line", "second line", "third line", "fourth line"] {
+        file.write_line(line).unwrap();
+    }
+    file.flush().unwrap();
+
+    let read = |path: &std::path::Path| std::fs::read_to_string(path).unwrap();
+    assert_eq!(read(&path), "fourth line\n");
+    assert_eq!(read(&file.rotated_path(1)), "third line\n");
+    assert_eq!(read(&file.rotated_path(2)), "second line\n");
+    assert!(!file.rotated_path(3).exists());
+}
+
+#[test]
+fn reopening_appends_to_the_current_file() {
+    let dir = common::temp_dir("append");
+    let path = dir.join("app.log");
+
+    RotatingFile::open(&path, 1024, 1).unwrap().write_line("before restart").unwrap();
+    RotatingFile::open(&path, 1024, 1).unwrap().write_line("after restart").unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "before restart\nafter restart\n");
+}
+
+#[test]
+fn records_reach_the_buffer_and_subscribers() {
+    logger::init(&LogSettings::default(), false);
+    let receiver = logger::subscribe();
+
+    log::warn!(mal_id = 417; "Portrait missing");
+    log::info!("Filtered out: only waifu_viewer logs info by default");
+
+    let line = receiver.try_recv().unwrap();
+    assert_eq!(line.level, Level::Warn);
+    assert_eq!(line.target, "logging");
+    assert_eq!(line.message, "Portrait missing mal_id=417");
+    assert!(receiver.try_recv().is_err());
+
+    let recent = logger::recent_lines();
+    assert!(recent.iter().any(|line| line.message == "Portrait missing mal_id=417"));
+    assert!(line.to_string().ends_with(" WARN  logging: Portrait missing mal_id=417"));
+}
@@ -0,0 +1,34 @@
+use waifu_viewer::ui::utils::markdown::to_pango;
+
+#[test]
+fn inline_formatting_becomes_markup() {
+    assert_eq!(
+        to_pango("**Best** girl, *no* _contest_, see `notes.md`"),
+        "<b>Best</b> girl, <i>no</i> <i>contest</i>, see <tt>notes.md</tt>"
+    );
+    assert_eq!(
+        to_pango("[MAL](https://myanimelist.net/character/417?a=1&b=2)"),
+        "<a href=\"https://myanimelist.net/character/417?a=1&amp;b=2\">MAL</a>"
+    );
+}
+
+#[test]
+fn headings_and_bullets_are_rendered_per_line() {
+    assert_eq!(
+        to_pango("# Lelouch\n## Plans\n- Zero Requiem\n* **Geass**"),
+        "<span size=\"large\"><b>Lelouch</b></span>\n<b>Plans</b>\n• Zero Requiem\n• <b>Geass</b>"
+    );
+}
+
+#[test]
+fn markup_characters_and_stray_markers_stay_literal() {
+    assert_eq!(to_pango("a < b & c > d"), "a &lt; b &amp; c &gt; d");
+    assert_eq!(to_pango("2 * 3 and snake_case_name"), "2 * 3 and snake_case_name");
+    assert_eq!(to_pango("#hashtag"), "#hashtag");
+}
+
+#[test]
+fn nested_markers_produce_nested_tags() {
+    assert_eq!(to_pango("**bold _and italic_**"), "<b>bold <i>and italic</i></b>");
+    assert_eq!(to_pango("[**Zero**](https://example.com)"), "<a href=\"https://example.com\"><b>Zero</b></a>");
+}
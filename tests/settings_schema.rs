@@ -0,0 +1,63 @@
+use std::path::Path;
+use std::process::Command;
+
+use waifu_viewer::api::search_query::{CharacterOrderBy, CharacterSearchQuery, SortDirection};
+use waifu_viewer::ui::utils::app_settings::{self, CardSize, ColorScheme};
+
+const SCHEMA: &str = "assets/schemas/com.example.WaifuViewer.gschema.xml";
+
+fn schema_source() -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(SCHEMA);
+    std::fs::read_to_string(path).expect("schema is readable")
+}
+
+#[test]
+fn schema_compiles_strictly() {
+    let status = Command::new("glib-compile-schemas")
+        .args(["--strict", "--dry-run"])
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/schemas"))
+        .status()
+        .expect("glib-compile-schemas runs");
+    assert!(status.success());
+}
+
+#[test]
+fn enum_nicks_match_the_schema() {
+    let schema = schema_source();
+    for size in CardSize::ALL {
+        assert!(schema.contains(&format!("nick=\"{}\"", size.nick())), "{} missing", size.nick());
+        assert_eq!(CardSize::from_nick(size.nick()), size);
+    }
+    for scheme in ColorScheme::ALL {
+        assert!(schema.contains(&format!("nick=\"{}\"", scheme.nick())), "{} missing", scheme.nick());
+        assert_eq!(ColorScheme::from_nick(scheme.nick()), scheme);
+    }
+}
+
+#[test]
+fn unknown_nicks_use_the_defaults() {
+    assert_eq!(CardSize::from_nick("huge"), CardSize::Medium);
+    assert_eq!(ColorScheme::from_nick(""), ColorScheme::System);
+    assert_eq!(CardSize::Medium.image_size(), (180, 270));
+}
+
+#[test]
+fn last_search_round_trips() {
+    let query = CharacterSearchQuery::new()
+        .query("Lelouch & Zero")
+        .order_by(CharacterOrderBy::Favorites)
+        .sort(SortDirection::Desc)
+        .letter('L')
+        .limit(10);
+
+    let stored = app_settings::encode_search(Some(&query));
+
+    assert_eq!(app_settings::decode_search(&stored), Some(query));
+}
+
+#[test]
+fn missing_or_unreadable_searches_restore_nothing() {
+    assert_eq!(app_settings::encode_search(None), "");
+    assert_eq!(app_settings::decode_search(""), None);
+    assert_eq!(app_settings::decode_search("{not json"), None);
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::{MockResponse, MockServer};
+use waifu_viewer::api::jikan::JikanResponse;
+use waifu_viewer::models::character::Character;
+use waifu_viewer::storage::favorites::FavoritesStorage;
+use waifu_viewer::storage::store::SCHEMA_VERSION;
+use waifu_viewer::storage::transfer::{self, ImportMode, TransferFormat};
+
+// Storage in a fresh directory, with its favorites file and portraits directory
+fn temp_storage(name: &str) -> (FavoritesStorage, PathBuf) {
+    let dir = common::temp_dir(name);
+    let file_path = dir.join("favorites.json");
+    (FavoritesStorage::with_paths(file_path.clone(), dir.join("portraits")), file_path)
+}
+
+// The recorded top characters, with portraits served by `server`
+fn characters(server: &MockServer) -> Vec<Character> {
+    let response: JikanResponse = serde_json::from_str(&common::fixture("top_characters.json")).unwrap();
+    response
+        .data
+        .into_iter()
+        .map(|mut character| {
+            let target = format!("/portraits/{}.jpg", character.mal_id);
+            server.route(&target, MockResponse::new(200, common::fixture_bytes("portrait.jpg")));
+            character.images.jpg.image_url = Some(server.url(&target));
+            character
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn favorites_and_portraits_are_stored() {
+    let server = MockServer::start();
+    let (storage, file_path) = temp_storage("add");
+    let lelouch = characters(&server).remove(0);
+
+    storage.add_favorite(lelouch.clone()).await.unwrap();
+
+    let record = storage.get_favorite(417).unwrap().unwrap();
+    assert_eq!(record.character, lelouch);
+    assert_eq!(
+        std::fs::read(storage.local_image_path(417)).unwrap(),
+        common::fixture_bytes("portrait.jpg")
+    );
+
+    // What reached the disk is a current-schema document
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&file_path).unwrap()).unwrap();
+    assert_eq!(saved["version"], SCHEMA_VERSION);
+    assert_eq!(saved["favorites"][0]["character"]["name"], "Lelouch Lamperouge");
+}
+
+#[tokio::test]
+async fn adding_twice_keeps_one_record() {
+    let server = MockServer::start();
+    let (storage, _) = temp_storage("dedupe");
+    let lelouch = characters(&server).remove(0);
+
+    storage.add_favorite(lelouch.clone()).await.unwrap();
+    storage.set_rating(417, Some(9)).await.unwrap();
+    storage.add_favorite(lelouch).await.unwrap();
+
+    let favorites = storage.get_favorites().unwrap();
+    assert_eq!(favorites.len(), 1);
+    // The second add leaves the user's data alone
+    assert_eq!(favorites[0].rating, Some(9));
+    // and does not download the portrait again
+    assert_eq!(server.request_count("/portraits/417.jpg"), 1);
+}
+
+#[tokio::test]
+async fn missing_portraits_do_not_block_saving() {
+    let server = MockServer::start();
+    let (storage, _) = temp_storage("no-portrait");
+    let mut levi = characters(&server).remove(1);
+    levi.images.jpg.image_url = Some(server.url("/portraits/missing.jpg"));
+
+    storage.add_favorite(levi).await.unwrap();
+
+    assert!(storage.is_favorite(40882).unwrap());
+    assert_eq!(storage.local_image_uri(40882), None);
+}
+
+#[tokio::test]
+async fn removing_deletes_the_record_and_portrait() {
+    let server = MockServer::start();
+    let (storage, _) = temp_storage("remove");
+    let lelouch = characters(&server).remove(0);
+
+    storage.add_favorite(lelouch.clone()).await.unwrap();
+    storage.remove_favorite(lelouch).await.unwrap();
+
+    assert!(!storage.is_favorite(417).unwrap());
+    assert!(!storage.local_image_path(417).exists());
+}
+
+#[tokio::test]
+async fn a_second_storage_on_the_same_file_sees_the_changes() {
+    let server = MockServer::start();
+    let (storage, file_path) = temp_storage("reopen");
+    for character in characters(&server) {
+        storage.add_favorite(character).await.unwrap();
+    }
+    storage.set_notes(71, "Sits like *that*").await.unwrap();
+
+    let reopened = FavoritesStorage::with_paths(file_path, PathBuf::from("unused"));
+
+    let ids: Vec<u32> = reopened.get_favorites().unwrap().iter().map(|record| record.mal_id()).collect();
+    assert_eq!(ids, [417, 40882, 71]);
+    assert_eq!(reopened.get_favorite(71).unwrap().unwrap().notes, "Sits like *that*");
+}
+
+#[tokio::test]
+async fn json_export_round_trips_into_a_fresh_collection() {
+    let server = MockServer::start();
+    let (storage, _) = temp_storage("export");
+    for character in characters(&server) {
+        storage.add_favorite(character).await.unwrap();
+    }
+    storage.set_rating(40882, Some(10)).await.unwrap();
+    storage.set_tags(417, vec!["strategist".to_string()]).await.unwrap();
+    let list_id = storage.create_list("Detectives").await.unwrap();
+    storage.set_list_membership(list_id, 71, true).await.unwrap();
+    storage.record_search("lelouch").await.unwrap();
+
+    let export_path = common::temp_dir("export-file").join("collection.json");
+    assert_eq!(storage.export(&export_path, TransferFormat::Json).await.unwrap(), 3);
+
+    let (restored, _) = temp_storage("import");
+    let report = restored.import(&export_path, TransferFormat::Json, ImportMode::Replace).await.unwrap();
+
+    assert_eq!(report.added, 3);
+    assert_eq!(report.failed, 0);
+    assert_eq!(restored.get_favorites().unwrap(), storage.get_favorites().unwrap());
+    assert_eq!(restored.get_tags(417).unwrap(), ["strategist"]);
+    let lists = restored.get_lists().unwrap();
+    assert_eq!(lists.len(), 1);
+    assert_eq!(lists[0].name, "Detectives");
+    assert_eq!(restored.get_lists_containing(71).unwrap(), [lists[0].id]);
+    // Search history stays with the install it was typed into
+    assert!(restored.get_search_history().unwrap().is_empty());
+    // Portraits were fetched again for the new collection
+    assert!(restored.local_image_uri(417).is_some());
+}
+
+#[tokio::test]
+async fn merging_skips_characters_already_saved() {
+    let server = MockServer::start();
+    let all = characters(&server);
+
+    let (source, _) = temp_storage("merge-source");
+    for character in &all {
+        source.add_favorite(character.clone()).await.unwrap();
+    }
+    let export_path = common::temp_dir("merge-file").join("collection.json");
+    source.export(&export_path, TransferFormat::Json).await.unwrap();
+
+    let (target, _) = temp_storage("merge-target");
+    target.add_favorite(all[0].clone()).await.unwrap();
+    target.set_rating(417, Some(7)).await.unwrap();
+
+    let report = target.import(&export_path, TransferFormat::Json, ImportMode::Merge).await.unwrap();
+
+    assert_eq!(report.added, 2);
+    assert_eq!(report.skipped, 1);
+    assert_eq!(target.get_favorites().unwrap().len(), 3);
+    assert_eq!(target.get_favorite(417).unwrap().unwrap().rating, Some(7));
+}
+
+#[test]
+fn import_files_are_deduplicated_by_id() {
+    let ids = transfer::parse("417\n71\n417\nhttps://myanimelist.net/character/71/L_Lawliet\n", TransferFormat::MalIds).unwrap();
+    let ids: Vec<u32> = ids.id_only.iter().map(|entry| entry.mal_id).collect();
+    assert_eq!(ids, [417, 71]);
+
+    let csv = "mal_id,name,rating\n417,Lelouch,9\n417,Lelouch again,3\n";
+    let rows = transfer::parse(csv, TransferFormat::Csv).unwrap();
+    assert_eq!(rows.id_only.len(), 1);
+    // The first occurrence wins
+    assert_eq!(rows.id_only[0].rating, Some(9));
+}
+
+#[tokio::test]
+async fn restoring_a_removed_favorite_brings_back_everything() {
+    let server = MockServer::start();
+    let (storage, _) = temp_storage("restore");
+    let lelouch = characters(&server).remove(0);
+    let thumbnail = server.url("/portraits/417-alt.jpg");
+    server.route("/portraits/417-alt.jpg", MockResponse::new(200, common::fixture_bytes("portrait.jpg")));
+
+    storage.add_favorite(lelouch.clone()).await.unwrap();
+    storage.set_rating(417, Some(5)).await.unwrap();
+    storage.set_notes(417, "All according to keikaku").await.unwrap();
+    storage.set_thumbnail(417, Some(thumbnail.clone())).await.unwrap();
+    storage.set_tags(417, vec!["strategist".to_string()]).await.unwrap();
+    let list_id = storage.create_list("Geass").await.unwrap();
+    storage.set_list_membership(list_id, 417, true).await.unwrap();
+    let before = storage.get_favorite(417).unwrap().unwrap();
+
+    let removed = storage.remove_favorite(lelouch).await.unwrap().unwrap();
+    assert!(!storage.local_image_path(417).exists());
+    storage.restore_favorite(removed).await.unwrap();
+
+    let after = storage.get_favorite(417).unwrap().unwrap();
+    assert_eq!(after.rating, Some(5));
+    assert_eq!(after.notes, "All according to keikaku");
+    assert_eq!(after.added_at, before.added_at);
+    assert_eq!(after.thumbnail_url.as_deref(), Some(thumbnail.as_str()));
+    assert_eq!(storage.get_tags(417).unwrap(), ["strategist"]);
+    assert_eq!(storage.get_lists_containing(417).unwrap(), [list_id]);
+    assert!(storage.local_image_path(417).exists());
+}
+
+#[test]
+fn a_pre_versioning_file_is_migrated_to_the_current_schema() {
+    let (storage, file_path) = temp_storage("migrate-v1");
+    std::fs::write(&file_path, common::fixture("favorites_v1.json")).unwrap();
+
+    let favorites = storage.get_favorites().unwrap();
+    let ids: Vec<u32> = favorites.iter().map(|record| record.mal_id()).collect();
+    assert_eq!(ids, [417, 40882]);
+    assert_eq!(favorites[0].character.name, "Lelouch Lamperouge");
+    for record in &favorites {
+        assert_eq!(record.rating, None);
+        assert_eq!(record.notes, "");
+        assert_eq!(record.thumbnail_url, None);
+    }
+    assert!(storage.get_lists().unwrap().is_empty());
+    assert!(storage.get_all_tags().unwrap().is_empty());
+    assert!(storage.get_search_history().unwrap().is_empty());
+    assert!(storage.get_saved_searches().unwrap().is_empty());
+
+    // The file was rewritten in the current schema, keeping the original as a backup
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&file_path).unwrap()).unwrap();
+    assert_eq!(saved["version"], SCHEMA_VERSION);
+    assert!(saved["favorites"][0]["rating"].is_null());
+    assert!(file_path.with_extension("v1.bak").exists());
+}